@@ -1,51 +1,1147 @@
-use chrono::NaiveDate;
+use crate::models::{
+    CompoundingMode, DayCount, InterestPeriod, LedgerEntry, PaymentAllocation, RateSegment,
+    RateBase, RateFormula, RateIndex, RateStep, RoundingMode, RoundingPolicy, RoundingStage,
+    ScheduleInterval, ScheduleRow,
+};
+use chrono::{Datelike, Months, NaiveDate};
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::collections::HashMap;
 
-pub fn compute_interest(principal: f64, rate: f64, days: i64) -> f64 {
+pub fn compute_interest(
+    principal: Decimal,
+    rate: Decimal,
+    from_date: NaiveDate,
+    to_date: NaiveDate,
+    day_count: DayCount,
+    rounding: RoundingPolicy,
+) -> Decimal {
     // Simple interest formula: Interest = Principal × Rate × Time
-    // Time is expressed as days / 365
-    let interest = principal * rate * (days as f64 / 365.0);
+    // Time is days / year basis under the day-count convention; dividing last
+    // keeps exact half-cent amounts exact before rounding. Per-day rounding
+    // rounds the per diem first and multiplies it by the days.
+    let interest: Decimal = accrual_fractions(from_date, to_date, day_count)
+        .into_iter()
+        .map(|(days, basis)| match rounding.stage {
+            RoundingStage::PerDay => {
+                round_money(principal * rate / Decimal::from(basis), rounding.mode)
+                    * Decimal::from(days)
+            }
+            RoundingStage::PerPeriod | RoundingStage::Final => {
+                principal * rate * Decimal::from(days) / Decimal::from(basis)
+            }
+        })
+        .sum();
     
-    // Round to 2 decimal places
-    (interest * 100.0).round() / 100.0
+    // Round to 2 decimal places unless rounding is deferred to the final total
+    match rounding.stage {
+        RoundingStage::PerPeriod => round_money(interest, rounding.mode),
+        RoundingStage::PerDay | RoundingStage::Final => interest,
+    }
+}
+
+pub fn calculate_days_between(
+    from_date: NaiveDate,
+    to_date: NaiveDate,
+    day_count: DayCount,
+) -> i64 {
+    match day_count {
+        DayCount::Thirty360 => thirty_360_days(from_date, to_date),
+        _ => (to_date - from_date).num_days(),
+    }
+}
+
+// The accrual period as (days, year basis) pieces. Actual/Actual has one piece
+// per calendar year, each over that year's length; the others have a single piece.
+fn accrual_fractions(
+    from_date: NaiveDate,
+    to_date: NaiveDate,
+    day_count: DayCount,
+) -> Vec<(i64, i64)> {
+    let days = calculate_days_between(from_date, to_date, day_count);
+    match day_count {
+        DayCount::Actual365 => vec![(days, 365)],
+        DayCount::Actual360 | DayCount::Thirty360 => vec![(days, 360)],
+        DayCount::ActualActual => {
+            let mut pieces = Vec::new();
+            let mut start = from_date;
+            while start < to_date {
+                let next_year = NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+                    .expect("January 1 is always valid");
+                let end = next_year.min(to_date);
+                let year_length = (next_year - first_of_year(start)).num_days();
+                pieces.push(((end - start).num_days(), year_length));
+                start = end;
+            }
+            pieces
+        }
+    }
+}
+
+// 30/360 (US) day count: a 31st is treated as the 30th, and an end date on the
+// 31st only rolls back when the start date is already on the 30th or 31st.
+fn thirty_360_days(from_date: NaiveDate, to_date: NaiveDate) -> i64 {
+    let d1 = from_date.day().min(30) as i64;
+    let d2 = if to_date.day() == 31 && d1 == 30 { 30 } else { to_date.day() as i64 };
+
+    360 * (to_date.year() - from_date.year()) as i64
+        + 30 * (to_date.month() as i64 - from_date.month() as i64)
+        + (d2 - d1)
+}
+
+// Evaluates a rate formula given the value (percent) of each index it names,
+// recording every operation so the result can be shown step by step.
+// Returns the rate in percent.
+pub fn evaluate_rate_formula(
+    formula: &RateFormula,
+    index_values: &HashMap<RateIndex, Decimal>,
+) -> Result<(Decimal, Vec<RateStep>), String> {
+    let mut steps = Vec::new();
+    let rate = evaluate_into(formula, index_values, &mut steps)?;
+    Ok((rate, steps))
 }
 
-pub fn calculate_days_between(from_date: NaiveDate, to_date: NaiveDate) -> i64 {
-    (to_date - from_date).num_days()
+fn evaluate_into(
+    formula: &RateFormula,
+    index_values: &HashMap<RateIndex, Decimal>,
+    steps: &mut Vec<RateStep>,
+) -> Result<Decimal, String> {
+    let mut rate = match &formula.base {
+        RateBase::Fixed { rate } => {
+            push_step(steps, format!("Fixed rate {}%", rate.normalize()), *rate)
+        }
+        RateBase::Index { index } => {
+            let value = index_values
+                .get(index)
+                .copied()
+                .ok_or_else(|| format!("No value available for {}", index.as_str()))?;
+            push_step(steps, format!("{} ({})", index.label(), index.as_str()), value)
+        }
+        RateBase::GreaterOf { formulas } | RateBase::LesserOf { formulas } => {
+            let greater = matches!(formula.base, RateBase::GreaterOf { .. });
+            let rates = formulas
+                .iter()
+                .map(|formula| evaluate_into(formula, index_values, steps))
+                .collect::<Result<Vec<_>, _>>()?;
+            let chosen = if greater { rates.iter().max() } else { rates.iter().min() }
+                .copied()
+                .ok_or_else(|| "Rate formula compares no rates".to_string())?;
+            let listed = rates
+                .iter()
+                .map(|rate| format!("{}%", rate.normalize()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let name = if greater { "Greater" } else { "Lesser" };
+            push_step(steps, format!("{} of {}", name, listed), chosen)
+        }
+    };
+
+    if let Some(increment) = formula.rounding_increment {
+        if increment <= Decimal::ZERO {
+            return Err("Rate rounding increment must be positive".to_string());
+        }
+        let rounded = (rate / increment)
+            .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+            * increment;
+        let description = format!("Rounded to the nearest {}%", increment.normalize());
+        rate = push_step(steps, description, rounded);
+    }
+    if !formula.spread.is_zero() {
+        let description = format!("Plus {}%", formula.spread.normalize());
+        rate = push_step(steps, description, rate + formula.spread);
+    }
+    if let Some(floor) = formula.floor {
+        rate = push_step(steps, format!("Floor of {}%", floor.normalize()), rate.max(floor));
+    }
+    if let Some(cap) = formula.cap {
+        rate = push_step(steps, format!("Cap of {}%", cap.normalize()), rate.min(cap));
+    }
+    Ok(rate)
+}
+
+fn push_step(steps: &mut Vec<RateStep>, description: String, rate: Decimal) -> Decimal {
+    let rate = rate.normalize();
+    steps.push(RateStep { description, rate });
+    rate
+}
+
+// Terms that govern how a balance accrues interest
+#[derive(Debug, Clone)]
+pub struct AccrualTerms {
+    // Annual rates as (effective date, rate) pairs in date order. The first
+    // rate applies from the start of the accrual whatever its date.
+    pub rates: Vec<(NaiveDate, Decimal)>,
+    pub compounding: CompoundingMode,
+    pub day_count: DayCount,
+    pub rounding: RoundingPolicy,
+    pub judgment_date: NaiveDate,
+}
+
+impl AccrualTerms {
+    fn rate_on(&self, date: NaiveDate) -> Decimal {
+        self.rates
+            .iter()
+            .rev()
+            .find(|(effective, _)| *effective <= date)
+            .or(self.rates.first())
+            .map_or(Decimal::ZERO, |(_, rate)| *rate)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Accrual {
+    pub periods: Vec<InterestPeriod>,
+    pub ledger: Vec<LedgerEntry>,
+    pub rate_segments: Vec<RateSegment>,
+    pub total_interest: Decimal,
+    // Principal and unpaid interest owed at the end date, net of payments
+    pub balance: Decimal,
+    pub per_diem: Decimal,
+    pub schedule: Vec<ScheduleRow>,
+}
+
+// Builds the (effective date, rate) schedule for an accrual from a base rate and
+// effective-dated rate periods given as (from, through, rate). Dates covered by
+// no period use the base rate; where periods overlap the later-starting wins.
+pub fn build_rate_schedule(
+    base_rate: Decimal,
+    rate_periods: &[(NaiveDate, Option<NaiveDate>, Decimal)],
+    from_date: NaiveDate,
+    to_date: NaiveDate,
+) -> Vec<(NaiveDate, Decimal)> {
+    let rate_on = |date: NaiveDate| {
+        rate_periods
+            .iter()
+            .filter(|(start, through, _)| *start <= date && through.is_none_or(|t| date <= t))
+            .max_by_key(|(start, _, _)| *start)
+            .map_or(base_rate, |(_, _, rate)| *rate)
+    };
+
+    let mut cuts: Vec<NaiveDate> = rate_periods
+        .iter()
+        .flat_map(|(start, through, _)| {
+            std::iter::once(*start).chain(through.and_then(|t| t.succ_opt()))
+        })
+        .filter(|date| *date > from_date && *date < to_date)
+        .chain(std::iter::once(from_date))
+        .collect();
+    cuts.sort();
+    cuts.dedup();
+
+    let mut schedule: Vec<(NaiveDate, Decimal)> = Vec::new();
+    for date in cuts {
+        let rate = rate_on(date);
+        if schedule.last().is_none_or(|(_, last)| *last != rate) {
+            schedule.push((date, rate));
+        }
+    }
+
+    schedule
+}
+
+// Accrues interest on the principal from from_date to to_date. Payments are
+// applied on their dates according to `allocation`; unpaid interest is
+// capitalized into the principal at each compounding date. Federal judgments
+// compound on each anniversary of the judgment under 28 U.S.C. §1961(b).
+//
+// Under per-day and per-period rounding each accrual segment is rounded to the
+// cent as it accrues; under final rounding balances carry full precision and
+// only the reported amounts are rounded.
+pub fn accrue(
+    principal: Decimal,
+    terms: &AccrualTerms,
+    from_date: NaiveDate,
+    to_date: NaiveDate,
+    payments: &[(NaiveDate, Decimal)],
+    allocation: PaymentAllocation,
+    interval: ScheduleInterval,
+) -> Accrual {
+    let rounding = terms.rounding;
+    let round = |amount: Decimal| round_money(amount, rounding.mode);
+
+    let compounding = compounding_dates(terms.compounding, terms.judgment_date, from_date, to_date);
+    let rate_changes = terms
+        .rates
+        .iter()
+        .map(|(date, _)| *date)
+        .filter(|date| *date > from_date && *date < to_date);
+
+    let mut boundaries: Vec<NaiveDate> = compounding
+        .iter()
+        .copied()
+        .chain(payments.iter().map(|(date, _)| *date))
+        .chain(rate_changes)
+        .chain(std::iter::once(to_date))
+        .collect();
+    boundaries.sort();
+    boundaries.dedup();
+
+    let mut payments = payments.to_vec();
+    payments.sort_by_key(|(date, _)| *date);
+    let mut payments = payments.into_iter().peekable();
+
+    let mut principal = principal;
+    let mut unpaid_interest = Decimal::ZERO;
+    let mut total_interest = Decimal::ZERO;
+    let mut start = from_date;
+
+    let mut periods = Vec::new();
+    let mut period_start = from_date;
+    let mut period_opening = principal;
+    let mut period_interest = Decimal::ZERO;
+
+    let mut ledger = Vec::new();
+    let mut row_start = from_date;
+    let mut row_interest = Decimal::ZERO;
+
+    let mut schedule_dates = schedule_dates(interval, from_date, to_date).into_iter().peekable();
+    let mut schedule = ScheduleBuilder {
+        rows: Vec::new(),
+        last_date: from_date,
+        last_cumulative: Decimal::ZERO,
+        paid: Decimal::ZERO,
+        day_count: terms.day_count,
+        mode: rounding.mode,
+    };
+
+    let mut rate_segments = Vec::new();
+    let mut segment_start = from_date;
+    let mut segment_rate = terms.rate_on(from_date);
+    let mut segment_interest = Decimal::ZERO;
+
+    for boundary in boundaries {
+        let rate = terms.rate_on(start);
+        if rate != segment_rate {
+            rate_segments.push(RateSegment {
+                start_date: segment_start.format("%Y-%m-%d").to_string(),
+                end_date: start.format("%Y-%m-%d").to_string(),
+                days: calculate_days_between(segment_start, start, terms.day_count),
+                rate: segment_rate * Decimal::ONE_HUNDRED,
+                interest: round(segment_interest),
+            });
+            segment_start = start;
+            segment_rate = rate;
+            segment_interest = Decimal::ZERO;
+        }
+
+        // Schedule rows that fall inside this stretch carry the interest accrued so far
+        while let Some(date) = schedule_dates.next_if(|date| *date < boundary) {
+            let accrued = compute_interest(principal, rate, start, date, terms.day_count, rounding);
+            schedule.push(date, total_interest + accrued, principal + unpaid_interest + accrued);
+        }
+
+        let interest =
+            compute_interest(principal, rate, start, boundary, terms.day_count, rounding);
+        segment_interest += interest;
+        unpaid_interest += interest;
+        total_interest += interest;
+        period_interest += interest;
+        row_interest += interest;
+        start = boundary;
+
+        while let Some((date, amount)) = payments.next_if(|(date, _)| *date == boundary) {
+            let (to_interest, to_principal) =
+                allocate_payment(amount, unpaid_interest, principal, allocation, rounding.mode);
+            unpaid_interest -= to_interest;
+            principal -= to_principal;
+            schedule.paid += amount;
+
+            ledger.push(LedgerEntry {
+                date: date.format("%Y-%m-%d").to_string(),
+                days: calculate_days_between(row_start, date, terms.day_count),
+                interest_accrued: round(row_interest),
+                payment: amount,
+                applied_to_interest: round(to_interest),
+                applied_to_principal: round(to_principal),
+                unpaid_interest: round(unpaid_interest),
+                principal_balance: round(principal),
+                balance: round(principal + unpaid_interest),
+            });
+            row_start = date;
+            row_interest = Decimal::ZERO;
+        }
+
+        if boundary == to_date || compounding.binary_search(&boundary).is_ok() {
+            periods.push(InterestPeriod {
+                start_date: period_start.format("%Y-%m-%d").to_string(),
+                end_date: boundary.format("%Y-%m-%d").to_string(),
+                days: calculate_days_between(period_start, boundary, terms.day_count),
+                opening_balance: round(period_opening),
+                interest: round(period_interest),
+                closing_balance: round(principal + unpaid_interest),
+            });
+
+            if boundary != to_date {
+                principal += unpaid_interest;
+                unpaid_interest = Decimal::ZERO;
+            }
+            period_start = boundary;
+            period_opening = principal;
+            period_interest = Decimal::ZERO;
+        }
+
+        if schedule_dates.next_if(|date| *date == boundary).is_some() {
+            schedule.push(boundary, total_interest, principal + unpaid_interest);
+        }
+    }
+
+    // Close the table with the balance as of the end date
+    if ledger.is_empty() || row_start < to_date {
+        ledger.push(LedgerEntry {
+            date: to_date.format("%Y-%m-%d").to_string(),
+            days: calculate_days_between(row_start, to_date, terms.day_count),
+            interest_accrued: round(row_interest),
+            payment: Decimal::ZERO,
+            applied_to_interest: Decimal::ZERO,
+            applied_to_principal: Decimal::ZERO,
+            unpaid_interest: round(unpaid_interest),
+            principal_balance: round(principal),
+            balance: round(principal + unpaid_interest),
+        });
+    }
+
+    rate_segments.push(RateSegment {
+        start_date: segment_start.format("%Y-%m-%d").to_string(),
+        end_date: to_date.format("%Y-%m-%d").to_string(),
+        days: calculate_days_between(segment_start, to_date, terms.day_count),
+        rate: segment_rate * Decimal::ONE_HUNDRED,
+        interest: round(segment_interest),
+    });
+
+    Accrual {
+        periods,
+        ledger,
+        rate_segments,
+        total_interest: round(total_interest),
+        balance: round(principal + unpaid_interest),
+        // Unpaid interest does not itself bear interest until it is capitalized
+        per_diem: round(
+            principal * terms.rate_on(to_date)
+                / Decimal::from(year_basis(to_date, terms.day_count)),
+        ),
+        schedule: schedule.rows,
+    }
+}
+
+// Collects the accrual schedule rows as the accrual walks forward
+struct ScheduleBuilder {
+    rows: Vec<ScheduleRow>,
+    last_date: NaiveDate,
+    last_cumulative: Decimal,
+    paid: Decimal,
+    day_count: DayCount,
+    mode: RoundingMode,
+}
+
+impl ScheduleBuilder {
+    fn push(&mut self, date: NaiveDate, cumulative_interest: Decimal, balance: Decimal) {
+        let cumulative_interest = round_money(cumulative_interest, self.mode);
+        self.rows.push(ScheduleRow {
+            date: date.format("%Y-%m-%d").to_string(),
+            days: calculate_days_between(self.last_date, date, self.day_count),
+            interest: cumulative_interest - self.last_cumulative,
+            payments: self.paid,
+            cumulative_interest,
+            balance: round_money(balance, self.mode),
+        });
+        self.last_date = date;
+        self.last_cumulative = cumulative_interest;
+        self.paid = Decimal::ZERO;
+    }
+}
+
+// Dates of the accrual schedule rows: each day or the first of each month
+// after from_date, ending with to_date
+fn schedule_dates(
+    interval: ScheduleInterval,
+    from_date: NaiveDate,
+    to_date: NaiveDate,
+) -> Vec<NaiveDate> {
+    let mode = match interval {
+        ScheduleInterval::Daily => CompoundingMode::Daily,
+        ScheduleInterval::Monthly => CompoundingMode::Monthly,
+    };
+    let mut dates = compounding_dates(mode, from_date, from_date, to_date);
+    dates.push(to_date);
+    dates
+}
+
+// Length of the year containing `date` under the day-count convention
+fn year_basis(date: NaiveDate, day_count: DayCount) -> i64 {
+    match day_count {
+        DayCount::Actual365 => 365,
+        DayCount::Actual360 | DayCount::Thirty360 => 360,
+        DayCount::ActualActual => {
+            let next_year = NaiveDate::from_ymd_opt(date.year() + 1, 1, 1)
+                .expect("January 1 is always valid");
+            (next_year - first_of_year(date)).num_days()
+        }
+    }
+}
+
+// Splits a payment into the amounts applied to interest and to principal. Any
+// amount beyond the outstanding balance is left unapplied.
+fn allocate_payment(
+    amount: Decimal,
+    unpaid_interest: Decimal,
+    principal: Decimal,
+    allocation: PaymentAllocation,
+    mode: RoundingMode,
+) -> (Decimal, Decimal) {
+    let to_interest = match allocation {
+        PaymentAllocation::UsRule => amount.min(unpaid_interest),
+        PaymentAllocation::ProRata => {
+            let balance = unpaid_interest + principal;
+            if balance <= Decimal::ZERO {
+                Decimal::ZERO
+            } else {
+                round_money(amount * unpaid_interest / balance, mode).min(unpaid_interest)
+            }
+        }
+    };
+    let to_principal = (amount - to_interest).min(principal);
+
+    (to_interest, to_principal)
+}
+
+fn round_money(amount: Decimal, mode: RoundingMode) -> Decimal {
+    let strategy = match mode {
+        RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+        RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+    };
+    amount.round_dp_with_strategy(2, strategy)
+}
+
+// Compounding dates strictly between from_date and to_date. Anniversaries of a
+// February 29 judgment roll to February 28 in common years.
+fn compounding_dates(
+    mode: CompoundingMode,
+    judgment_date: NaiveDate,
+    from_date: NaiveDate,
+    to_date: NaiveDate,
+) -> Vec<NaiveDate> {
+    let (anchor, step) = match mode {
+        CompoundingMode::None => return Vec::new(),
+        CompoundingMode::Daily => {
+            return from_date
+                .iter_days()
+                .skip(1)
+                .take_while(|day| *day < to_date)
+                .collect()
+        }
+        CompoundingMode::Anniversary => (judgment_date, 12),
+        CompoundingMode::Annual => (first_of_year(from_date), 12),
+        CompoundingMode::SemiAnnual => (first_of_year(from_date), 6),
+        CompoundingMode::Monthly => (first_of_year(from_date), 1),
+    };
+
+    (1..)
+        .map_while(|n| anchor.checked_add_months(Months::new(step * n)))
+        .skip_while(|date| *date <= from_date)
+        .take_while(|date| *date < to_date)
+        .collect()
+}
+
+fn first_of_year(date: NaiveDate) -> NaiveDate {
+    NaiveDate::from_ymd_opt(date.year(), 1, 1).expect("January 1 is always valid")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_decimal_macros::dec;
+
+    fn anniversary_terms(judgment_date: NaiveDate) -> AccrualTerms {
+        AccrualTerms {
+            rates: vec![(judgment_date, dec!(0.05))],
+            compounding: CompoundingMode::Anniversary,
+            day_count: DayCount::Actual365,
+            rounding: RoundingPolicy::default(),
+            judgment_date,
+        }
+    }
 
     #[test]
     fn test_compute_interest() {
         // Test case: $10,000 at 5% for 365 days
-        let principal = 10000.0;
-        let rate = 0.05;
-        let days = 365;
+        let principal = dec!(10000);
+        let rate = dec!(0.05);
+        let from_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
         
-        let interest = compute_interest(principal, rate, days);
-        assert_eq!(interest, 500.0);
+        let interest = compute_interest(
+            principal,
+            rate,
+            from_date,
+            to_date,
+            DayCount::Actual365,
+            RoundingPolicy::default(),
+        );
+        assert_eq!(interest, dec!(500));
     }
     
     #[test]
     fn test_compute_interest_partial_year() {
         // Test case: $10,000 at 10% for 180 days
-        let principal = 10000.0;
-        let rate = 0.10;
-        let days = 180;
+        let principal = dec!(10000);
+        let rate = dec!(0.10);
+        let from_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2023, 6, 30).unwrap();
         
-        let interest = compute_interest(principal, rate, days);
+        let interest = compute_interest(
+            principal,
+            rate,
+            from_date,
+            to_date,
+            DayCount::Actual365,
+            RoundingPolicy::default(),
+        );
         // Should be approximately 493.15
-        assert!((interest - 493.15).abs() < 0.01);
+        assert!((interest - dec!(493.15)).abs() < dec!(0.01));
     }
     
+    #[test]
+    fn test_compute_interest_rounds_exact_half_cent_up() {
+        // $36.50 at 5% for one day is exactly half a cent
+        let from_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+
+        let interest = compute_interest(
+            dec!(36.50),
+            dec!(0.05),
+            from_date,
+            to_date,
+            DayCount::Actual365,
+            RoundingPolicy::default(),
+        );
+        assert_eq!(interest, dec!(0.01));
+    }
+
+    #[test]
+    fn test_compute_interest_half_even_rounds_to_even_cent() {
+        let from_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+        let rounding = RoundingPolicy {
+            stage: RoundingStage::PerPeriod,
+            mode: RoundingMode::HalfEven,
+        };
+
+        // 0.005 rounds down to the even cent, 0.015 rounds up to it
+        let interest = |principal| {
+            compute_interest(
+                principal,
+                dec!(0.05),
+                from_date,
+                to_date,
+                DayCount::Actual365,
+                rounding,
+            )
+        };
+        assert_eq!(interest(dec!(36.50)), dec!(0.00));
+        assert_eq!(interest(dec!(109.50)), dec!(0.02));
+    }
+
+    #[test]
+    fn test_per_day_rounding_rounds_per_diem() {
+        // $10,000 at 5% accrues 1.369863... a day; per-day rounding charges 1.37
+        let from_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2023, 4, 11).unwrap();
+        let rounding = RoundingPolicy {
+            stage: RoundingStage::PerDay,
+            mode: RoundingMode::HalfUp,
+        };
+
+        let interest = compute_interest(
+            dec!(10000),
+            dec!(0.05),
+            from_date,
+            to_date,
+            DayCount::Actual365,
+            rounding,
+        );
+        assert_eq!(interest, dec!(137.00));
+
+        let per_period = compute_interest(
+            dec!(10000),
+            dec!(0.05),
+            from_date,
+            to_date,
+            DayCount::Actual365,
+            RoundingPolicy::default(),
+        );
+        assert_eq!(per_period, dec!(136.99));
+    }
+
+    #[test]
+    fn test_final_rounding_defers_to_total() {
+        // $27 at 5% accrues 0.0036986... a day. Split into two one-day segments,
+        // each segment rounds to nothing; carried at full precision the two
+        // days round to a cent.
+        let from_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let split_date = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2023, 1, 3).unwrap();
+        let accrue_with = |stage| {
+            let terms = AccrualTerms {
+                rates: vec![(from_date, dec!(0.05)), (split_date, dec!(0.05))],
+                compounding: CompoundingMode::None,
+                rounding: RoundingPolicy {
+                    stage,
+                    mode: RoundingMode::HalfUp,
+                },
+                ..anniversary_terms(from_date)
+            };
+            accrue(
+                dec!(27),
+                &terms,
+                from_date,
+                to_date,
+                &[],
+                PaymentAllocation::UsRule,
+                ScheduleInterval::Monthly,
+            )
+        };
+
+        assert_eq!(accrue_with(RoundingStage::PerPeriod).total_interest, dec!(0));
+        let final_stage = accrue_with(RoundingStage::Final);
+        assert_eq!(final_stage.total_interest, dec!(0.01));
+        assert_eq!(final_stage.balance, dec!(27.01));
+    }
+
     #[test]
     fn test_calculate_days_between() {
         let from_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
         let to_date = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
         
-        let days = calculate_days_between(from_date, to_date);
+        let days = calculate_days_between(from_date, to_date, DayCount::Actual365);
         assert_eq!(days, 365); // 2024 is a leap year, so 366 - 1
     }
+
+    #[test]
+    fn test_leap_year_day_counts() {
+        // Test case: $10,000 at 5% for all of 2024 (366 days)
+        let from_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        let interest = |day_count| {
+            compute_interest(
+                dec!(10000),
+                dec!(0.05),
+                from_date,
+                to_date,
+                day_count,
+                RoundingPolicy::default(),
+            )
+        };
+
+        assert_eq!(interest(DayCount::Actual365), dec!(501.37));
+        assert_eq!(interest(DayCount::ActualActual), dec!(500));
+        assert_eq!(interest(DayCount::Actual360), dec!(508.33));
+        assert_eq!(interest(DayCount::Thirty360), dec!(500));
+    }
+
+    #[test]
+    fn test_actual_actual_spanning_years() {
+        // Half of 2023 over 365 plus half of 2024 over 366
+        let from_date = NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+
+        let fractions = accrual_fractions(from_date, to_date, DayCount::ActualActual);
+        assert_eq!(fractions, vec![(184, 365), (182, 366)]);
+    }
+
+    #[test]
+    fn test_thirty_360_days() {
+        let from_date = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        assert_eq!(calculate_days_between(from_date, to_date, DayCount::Thirty360), 60);
+
+        let from_date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        assert_eq!(calculate_days_between(from_date, to_date, DayCount::Thirty360), 76);
+    }
+
+    #[test]
+    fn test_anniversary_compounding() {
+        // Test case: $10,000 at 5% from a 2021-03-15 judgment for two and a half years
+        let judgment_date = NaiveDate::from_ymd_opt(2021, 3, 15).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2023, 9, 15).unwrap();
+
+        let terms = anniversary_terms(judgment_date);
+        let periods = accrue(
+            dec!(10000),
+            &terms,
+            judgment_date,
+            to_date,
+            &[],
+            PaymentAllocation::UsRule,
+            ScheduleInterval::Monthly,
+        )
+        .periods;
+
+        assert_eq!(periods.len(), 3);
+        assert_eq!(periods[0].end_date, "2022-03-15");
+        assert_eq!(periods[0].interest, dec!(500));
+        assert_eq!(periods[1].opening_balance, dec!(10500));
+        assert_eq!(periods[1].interest, dec!(525));
+        assert_eq!(periods[2].opening_balance, dec!(11025));
+        assert_eq!(periods[2].days, 184);
+        assert_eq!(periods[2].interest, dec!(277.89));
+        assert_eq!(periods[2].closing_balance, dec!(11302.89));
+    }
+
+    #[test]
+    fn test_anniversary_compounding_starts_mid_year() {
+        // Interest computed from a later from_date still compounds on the judgment anniversary
+        let judgment_date = NaiveDate::from_ymd_opt(2020, 2, 29).unwrap();
+        let from_date = NaiveDate::from_ymd_opt(2020, 6, 1).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2021, 6, 1).unwrap();
+
+        let terms = anniversary_terms(judgment_date);
+        let periods = accrue(
+            dec!(10000),
+            &terms,
+            from_date,
+            to_date,
+            &[],
+            PaymentAllocation::UsRule,
+            ScheduleInterval::Monthly,
+        )
+        .periods;
+
+        assert_eq!(periods.len(), 2);
+        assert_eq!(periods[0].end_date, "2021-02-28");
+        assert_eq!(periods[1].start_date, "2021-02-28");
+        assert_eq!(periods[0].days + periods[1].days, 365);
+    }
+
+    #[test]
+    fn test_calendar_compounding_dates() {
+        let judgment_date = NaiveDate::from_ymd_opt(2022, 5, 10).unwrap();
+        let from_date = judgment_date;
+        let to_date = NaiveDate::from_ymd_opt(2023, 2, 1).unwrap();
+
+        let annual = compounding_dates(CompoundingMode::Annual, judgment_date, from_date, to_date);
+        assert_eq!(annual, vec![NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()]);
+
+        let semi_annual =
+            compounding_dates(CompoundingMode::SemiAnnual, judgment_date, from_date, to_date);
+        assert_eq!(
+            semi_annual,
+            vec![
+                NaiveDate::from_ymd_opt(2022, 7, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+            ]
+        );
+
+        let monthly =
+            compounding_dates(CompoundingMode::Monthly, judgment_date, from_date, to_date);
+        assert_eq!(monthly.len(), 8);
+        assert_eq!(monthly[0], NaiveDate::from_ymd_opt(2022, 6, 1).unwrap());
+
+        let daily = compounding_dates(CompoundingMode::Daily, judgment_date, from_date, to_date);
+        assert_eq!(daily.len() as i64, (to_date - from_date).num_days() - 1);
+    }
+
+    #[test]
+    fn test_no_compounding_matches_simple_interest() {
+        let judgment_date = NaiveDate::from_ymd_opt(2021, 3, 15).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2023, 3, 15).unwrap();
+
+        let terms = AccrualTerms {
+            compounding: CompoundingMode::None,
+            ..anniversary_terms(judgment_date)
+        };
+        let periods = accrue(
+            dec!(10000),
+            &terms,
+            judgment_date,
+            to_date,
+            &[],
+            PaymentAllocation::UsRule,
+            ScheduleInterval::Monthly,
+        )
+        .periods;
+
+        assert_eq!(periods.len(), 1);
+        assert_eq!(periods[0].interest, dec!(1000));
+    }
+
+    #[test]
+    fn test_us_rule_applies_payment_to_interest_first() {
+        // $10,000 at 5% simple; $300 paid after 365 days against $500 of interest
+        let judgment_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let payment_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let terms = AccrualTerms {
+            compounding: CompoundingMode::None,
+            ..anniversary_terms(judgment_date)
+        };
+
+        let accrual = accrue(
+            dec!(10000),
+            &terms,
+            judgment_date,
+            to_date,
+            &[(payment_date, dec!(300))],
+            PaymentAllocation::UsRule,
+            ScheduleInterval::Monthly,
+        );
+
+        assert_eq!(accrual.ledger.len(), 2);
+        let payment = &accrual.ledger[0];
+        assert_eq!(payment.interest_accrued, dec!(500));
+        assert_eq!(payment.applied_to_interest, dec!(300));
+        assert_eq!(payment.applied_to_principal, dec!(0));
+        assert_eq!(payment.unpaid_interest, dec!(200));
+        assert_eq!(payment.balance, dec!(10200));
+
+        // Unpaid interest does not itself bear interest
+        let closing = &accrual.ledger[1];
+        assert_eq!(closing.interest_accrued, dec!(501.37));
+        assert_eq!(closing.balance, dec!(10701.37));
+    }
+
+    #[test]
+    fn test_us_rule_payment_reduces_principal() {
+        let judgment_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let payment_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let terms = AccrualTerms {
+            compounding: CompoundingMode::None,
+            ..anniversary_terms(judgment_date)
+        };
+
+        let accrual = accrue(
+            dec!(10000),
+            &terms,
+            judgment_date,
+            to_date,
+            &[(payment_date, dec!(2500))],
+            PaymentAllocation::UsRule,
+            ScheduleInterval::Monthly,
+        );
+
+        let payment = &accrual.ledger[0];
+        assert_eq!(payment.applied_to_interest, dec!(500));
+        assert_eq!(payment.applied_to_principal, dec!(2000));
+        assert_eq!(payment.principal_balance, dec!(8000));
+        assert_eq!(accrual.ledger[1].interest_accrued, dec!(401.1));
+    }
+
+    #[test]
+    fn test_pro_rata_allocation() {
+        // $500 interest and $10,000 principal outstanding: a $2,100 payment splits 100/2,000
+        let judgment_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let payment_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let terms = AccrualTerms {
+            compounding: CompoundingMode::None,
+            ..anniversary_terms(judgment_date)
+        };
+
+        let accrual = accrue(
+            dec!(10000),
+            &terms,
+            judgment_date,
+            payment_date,
+            &[(payment_date, dec!(2100))],
+            PaymentAllocation::ProRata,
+            ScheduleInterval::Monthly,
+        );
+
+        assert_eq!(accrual.ledger.len(), 1);
+        assert_eq!(accrual.ledger[0].applied_to_interest, dec!(100));
+        assert_eq!(accrual.ledger[0].applied_to_principal, dec!(2000));
+        assert_eq!(accrual.ledger[0].balance, dec!(8400));
+    }
+
+    #[test]
+    fn test_unpaid_interest_capitalizes_on_anniversary() {
+        // $200 paid against the $252.05 accrued by then; the unpaid $300 of the year compounds
+        let judgment_date = NaiveDate::from_ymd_opt(2021, 3, 15).unwrap();
+        let payment_date = NaiveDate::from_ymd_opt(2021, 9, 15).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2023, 3, 15).unwrap();
+
+        let accrual = accrue(
+            dec!(10000),
+            &anniversary_terms(judgment_date),
+            judgment_date,
+            to_date,
+            &[(payment_date, dec!(200))],
+            PaymentAllocation::UsRule,
+            ScheduleInterval::Monthly,
+        );
+
+        assert_eq!(accrual.ledger[0].applied_to_interest, dec!(200));
+        assert_eq!(accrual.ledger[0].unpaid_interest, dec!(52.05));
+        assert_eq!(accrual.periods.len(), 2);
+        assert_eq!(accrual.periods[0].interest, dec!(500));
+        assert_eq!(accrual.periods[0].closing_balance, dec!(10300));
+        assert_eq!(accrual.periods[1].opening_balance, dec!(10300));
+        assert_eq!(accrual.periods[1].interest, dec!(515));
+    }
+
+    #[test]
+    fn test_build_rate_schedule() {
+        let from_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let rate_periods = [
+            (
+                NaiveDate::from_ymd_opt(2022, 7, 1).unwrap(),
+                Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap()),
+                dec!(0.04),
+            ),
+            (NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), None, dec!(0.06)),
+        ];
+
+        let schedule = build_rate_schedule(dec!(0.05), &rate_periods, from_date, to_date);
+
+        assert_eq!(
+            schedule,
+            vec![
+                (from_date, dec!(0.04)),
+                (NaiveDate::from_ymd_opt(2023, 7, 1).unwrap(), dec!(0.05)),
+                (NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), dec!(0.06)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_accrual_splits_at_rate_changes() {
+        // $10,000 simple interest: 4% for the first half of 2023, 6% for the second
+        let from_date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let change_date = NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let terms = AccrualTerms {
+            rates: vec![(from_date, dec!(0.04)), (change_date, dec!(0.06))],
+            compounding: CompoundingMode::None,
+            ..anniversary_terms(from_date)
+        };
+
+        let accrual = accrue(
+            dec!(10000),
+            &terms,
+            from_date,
+            to_date,
+            &[],
+            PaymentAllocation::UsRule,
+            ScheduleInterval::Monthly,
+        );
+
+        assert_eq!(accrual.rate_segments.len(), 2);
+        assert_eq!(accrual.rate_segments[0].days, 181);
+        assert_eq!(accrual.rate_segments[0].rate, dec!(4));
+        assert_eq!(accrual.rate_segments[0].interest, dec!(198.36));
+        assert_eq!(accrual.rate_segments[1].days, 184);
+        assert_eq!(accrual.rate_segments[1].interest, dec!(302.47));
+        assert_eq!(accrual.periods[0].interest, dec!(500.83));
+    }
+
+    #[test]
+    fn test_monthly_schedule_tracks_cumulative_balance() {
+        // $36,500 at 5% simple interest accrues exactly $5 a day
+        let from_date = NaiveDate::from_ymd_opt(2023, 1, 15).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2023, 3, 10).unwrap();
+        let terms = AccrualTerms {
+            compounding: CompoundingMode::None,
+            ..anniversary_terms(from_date)
+        };
+        let payment_date = NaiveDate::from_ymd_opt(2023, 2, 10).unwrap();
+
+        let accrual = accrue(
+            dec!(36500),
+            &terms,
+            from_date,
+            to_date,
+            &[(payment_date, dec!(100))],
+            PaymentAllocation::UsRule,
+            ScheduleInterval::Monthly,
+        );
+
+        let rows: Vec<_> = accrual
+            .schedule
+            .iter()
+            .map(|row| {
+                (row.date.as_str(), row.days, row.interest, row.payments, row.cumulative_interest)
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("2023-02-01", 17, dec!(85), dec!(0), dec!(85)),
+                ("2023-03-01", 28, dec!(140), dec!(100), dec!(225)),
+                ("2023-03-10", 9, dec!(45), dec!(0), dec!(270)),
+            ]
+        );
+        assert_eq!(accrual.schedule[1].balance, dec!(36625));
+        assert_eq!(accrual.schedule.last().unwrap().balance, accrual.balance);
+        assert_eq!(accrual.per_diem, dec!(5));
+    }
+
+    #[test]
+    fn test_daily_schedule_has_a_row_per_day() {
+        let from_date = NaiveDate::from_ymd_opt(2024, 2, 27).unwrap();
+        let to_date = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let terms = AccrualTerms {
+            day_count: DayCount::ActualActual,
+            ..anniversary_terms(from_date)
+        };
+
+        let accrual = accrue(
+            dec!(36600),
+            &terms,
+            from_date,
+            to_date,
+            &[],
+            PaymentAllocation::UsRule,
+            ScheduleInterval::Daily,
+        );
+
+        assert_eq!(accrual.schedule.len(), 4);
+        assert!(accrual.schedule.iter().all(|row| row.days == 1 && row.interest == dec!(5)));
+        assert_eq!(accrual.per_diem, dec!(5));
+    }
+
+    #[test]
+    fn test_rate_formula_floor_and_cap() {
+        // Texas: prime rate, not less than 5% nor more than 15%
+        let formula = RateFormula::index(RateIndex::Prime, Decimal::ZERO)
+            .with_floor(dec!(5))
+            .with_cap(dec!(15));
+        let rate_with_prime = |prime| {
+            let values = HashMap::from([(RateIndex::Prime, prime)]);
+            evaluate_rate_formula(&formula, &values).unwrap().0
+        };
+
+        assert_eq!(rate_with_prime(dec!(3.25)), dec!(5));
+        assert_eq!(rate_with_prime(dec!(8.5)), dec!(8.5));
+        assert_eq!(rate_with_prime(dec!(16)), dec!(15));
+    }
+
+    #[test]
+    fn test_rate_formula_greater_of_with_rounded_index() {
+        // The greater of 6% or the Treasury rate rounded to 1/8% plus 2%
+        let index = RateFormula {
+            rounding_increment: Some(dec!(0.125)),
+            ..RateFormula::index(RateIndex::Treasury1Year, dec!(2))
+        };
+        let formula = RateFormula {
+            base: RateBase::GreaterOf {
+                formulas: vec![RateFormula::fixed(dec!(6)), index],
+            },
+            ..RateFormula::fixed(Decimal::ZERO)
+        };
+        let values = HashMap::from([(RateIndex::Treasury1Year, dec!(4.83))]);
+
+        let (rate, steps) = evaluate_rate_formula(&formula, &values).unwrap();
+
+        assert_eq!(rate, dec!(6.875));
+        let rates: Vec<Decimal> = steps.iter().map(|step| step.rate).collect();
+        assert_eq!(rates, vec![dec!(6), dec!(4.83), dec!(4.875), dec!(6.875), dec!(6.875)]);
+        assert_eq!(steps.last().unwrap().description, "Greater of 6%, 6.875%");
+    }
+
+    #[test]
+    fn test_rate_formula_requires_index_values() {
+        let formula = RateFormula::index(RateIndex::Prime, dec!(4));
+        assert!(evaluate_rate_formula(&formula, &HashMap::new()).is_err());
+    }
 }
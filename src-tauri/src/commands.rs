@@ -1,160 +1,1119 @@
-use crate::calculator::{calculate_days_between, compute_interest};
-use crate::db::{delete_state_rate, get_all_states, get_state_rate, update_state_rate};
-use crate::models::{CalcRequest, CalcResponse, StateRate};
-use crate::rate_fetcher::{get_fallback_federal_rate, get_federal_rate};
+use crate::calculator::{
+    accrue, build_rate_schedule, calculate_days_between, evaluate_rate_formula, AccrualTerms,
+};
+use crate::db::{
+    create_rate_period, create_state_rate, delete_calculation, delete_matter, delete_rate_period,
+    delete_state_rate, get_all_states, get_audit_entry, get_calculation, get_calculations,
+    get_connection, get_db_path, get_matter, get_matters, get_overdue_states, get_rate_periods,
+    get_state_rate, get_state_rate_audit, get_state_rate_by_id, get_trashed_states,
+    insert_calculation, insert_matter, purge_state_rate, rename_calculation, replace_rate_periods,
+    restore_state_rate, revert_state_rate, state_name_taken, update_matter, update_rate_period,
+    update_state_rate, MISSING_INDEX_NOTE,
+};
+use crate::error::{AppError, ErrorCode};
+use crate::lint::{self, LintReport};
+use crate::models::{
+    AuditContext, AuditSnapshot, CalcRequest, CalcResponse, CalcWarning, CalculationSummary,
+    CompoundingMode, DayCount, Matter, RateDateRule, RateFormula, RateIndex, RateSource,
+    RoundingPolicy, SavedCalculation, StateRate, StateRateAudit, StateRatePeriod, WarningCode,
+};
+use crate::rate_fetcher::{
+    get_fallback_federal_rate, get_index_rate, observation_window, FallbackCause, FetchError,
+    ObservationSource,
+};
+use crate::rate_table::{
+    diff_rows, export_rows, parse_rows, ImportPreview, RateTableRow, TableFormat,
+};
+use crate::seed::{self, ResetReport, SeedConflict};
 use chrono::NaiveDate;
-use tauri::{AppHandle, Manager};
+use rusqlite::Connection;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Manager};
+
+// Cited for federal matters when there is no Federal row
+const FEDERAL_CITATION: &str = "28 U.S.C. § 1961";
+const FEDERAL_SOURCE_URL: &str = "https://www.law.cornell.edu/uscode/text/28/1961";
 
 #[tauri::command]
 pub fn calculate(
     app: AppHandle,
     request: CalcRequest,
-) -> Result<CalcResponse, String> {
+) -> Result<CalcResponse, AppError> {
     // Parse dates
-    let judgment_date = NaiveDate::parse_from_str(&request.judgment_date, "%Y-%m-%d")
-        .map_err(|e| format!("Invalid judgment date: {}", e))?;
-    let from_date = NaiveDate::parse_from_str(&request.from_date, "%Y-%m-%d")
-        .map_err(|e| format!("Invalid from date: {}", e))?;
-    let to_date = NaiveDate::parse_from_str(&request.to_date, "%Y-%m-%d")
-        .map_err(|e| format!("Invalid to date: {}", e))?;
+    let judgment_date = parse_date(&request.judgment_date, "judgment date")?;
+    let from_date = parse_date(&request.from_date, "from date")?;
+    let to_date = parse_date(&request.to_date, "to date")?;
 
     // Validate date range
     if from_date > to_date {
-        return Err("From date must be before or equal to end date".to_string());
+        return Err(AppError::invalid_input("From date must be before or equal to end date"));
     }
 
-    // Get rate based on federal or state
-    let (rate, rate_source) = if request.is_federal {
-        // Fetch federal rate from FRED API
-        let api_key = get_api_key(&app)?;
-        match get_federal_rate(judgment_date, &api_key) {
-            Ok(rate) => (rate, "Federal Rate (1-Year Treasury)".to_string()),
-            Err(e) => {
-                log::warn!("Failed to fetch federal rate: {}", e);
-                (
-                    get_fallback_federal_rate(),
-                    "Federal Rate (Cached/Default)".to_string(),
-                )
-            }
+    let mut warnings = Vec::new();
+    if from_date < judgment_date {
+        warnings.push(CalcWarning::new(
+            WarningCode::FromDateBeforeJudgment,
+            format!(
+                "Interest runs from {}, before the judgment date {}; post-judgment rates may not apply",
+                from_date, judgment_date
+            ),
+        ));
+    }
+    let today = chrono::Local::now().date_naive();
+    if to_date > today {
+        warnings.push(CalcWarning::new(
+            WarningCode::FutureEndDate,
+            format!("The end date {} is in the future; interest is projected", to_date),
+        ));
+    }
+
+    // Look up the jurisdiction's conventions; federal matters fall back to
+    // 28 U.S.C. §1961(b) (annual compounding, Actual/365) without a Federal row
+    let jurisdiction_name = if request.is_federal { "Federal" } else { request.state.as_str() };
+    let jurisdiction = get_state_rate(&app, jurisdiction_name)?;
+    let (compounding, jurisdiction_day_count, rounding) = match &jurisdiction {
+        Some(row) => (row.compounding, row.day_count, row.rounding),
+        None => (CompoundingMode::Anniversary, DayCount::Actual365, RoundingPolicy::default()),
+    };
+    let day_count = request.day_count.unwrap_or(jurisdiction_day_count);
+    match &jurisdiction {
+        Some(row) => warnings.extend(rate_row_warnings(row, today)),
+        None if request.is_federal => warnings.push(CalcWarning::new(
+            WarningCode::JurisdictionDefaults,
+            "No Federal rate row; 28 U.S.C. §1961 defaults were assumed",
+        )),
+        None => {}
+    }
+
+    // A state's effective-dated rate history overrides its base rate where it applies
+    let rate_periods = match &jurisdiction {
+        Some(row) if !request.is_federal => get_rate_periods(&get_connection(&app)?, row.id)?
+            .iter()
+            .map(parse_rate_period)
+            .collect::<Result<Vec<_>, AppError>>()?,
+        _ => Vec::new(),
+    };
+
+    // Evaluate the jurisdiction's rate formula against the indexes it names;
+    // federal matters without a Federal row use the 1-year Treasury rate
+    let formula = match &jurisdiction {
+        Some(row) => row.formula.clone(),
+        None if request.is_federal => RateFormula::index(RateIndex::Treasury1Year, Decimal::ZERO),
+        None => {
+            return Err(AppError::new(
+                ErrorCode::StateNotFound,
+                format!("State '{}' not found in database", request.state),
+            ))
         }
-    } else {
-        // Get state rate from database
-        match get_state_rate(&app, &request.state) {
-            Ok(Some(state_rate)) => {
-                if state_rate.is_variable {
-                    // Handle variable rates (e.g., Federal + X%)
-                    let api_key = get_api_key(&app)?;
-                    let base_rate = match get_federal_rate(judgment_date, &api_key) {
-                        Ok(r) => r,
-                        Err(_) => get_fallback_federal_rate(),
+    };
+
+    // The jurisdiction's rule picks the index observations that fix the rate
+    let rate_date_rule = jurisdiction
+        .as_ref()
+        .map_or(RateDateRule::PrecedingWeek, |row| row.rate_date_rule);
+    let rate_date = match rate_date_rule {
+        RateDateRule::VerdictDate => {
+            let verdict_date = request.verdict_date.as_deref().ok_or_else(|| {
+                AppError::invalid_input(format!(
+                    "{} fixes the rate on the verdict date; enter a verdict date",
+                    jurisdiction_name
+                ))
+            })?;
+            parse_date(verdict_date, "verdict date")?
+        }
+        _ => judgment_date,
+    };
+    let window = observation_window(rate_date_rule, rate_date);
+
+    // Index observations are read from the local cache first, so an API key is
+    // only needed for dates not fetched before
+    let conn = get_connection(&app)?;
+    let api_key = get_api_key(&app).unwrap_or_default();
+    let mut index_values = HashMap::new();
+    let mut index_sources = Vec::new();
+    let mut used_fallback = false;
+    for index in formula.indexes() {
+        let value = match get_index_rate(&conn, index, window, &api_key) {
+            Ok(index_rate) => {
+                index_sources.push(format!("{} {}", index.as_str(), index_rate.source.describe()));
+                if let Some(cause) = index_rate.source.cause() {
+                    let code = match (cause, &index_rate.source) {
+                        (FallbackCause::FetchFailed(_), _) => WarningCode::IndexFetchFailed,
+                        (FallbackCause::NotPublished, ObservationSource::StaleCache { .. }) => {
+                            WarningCode::StaleIndexData
+                        }
+                        (FallbackCause::NotPublished, _) => WarningCode::PartialIndexData,
                     };
-                    let final_rate = base_rate + (state_rate.plus_percentage / 100.0);
-                    (
-                        final_rate,
-                        format!("{} (Variable: Federal + {}%)", state_rate.state, state_rate.plus_percentage),
-                    )
-                } else {
-                    (
-                        state_rate.rate / 100.0, // Convert percentage to decimal
-                        format!("{} (Fixed: {}%)", state_rate.state, state_rate.rate),
-                    )
+                    warnings.push(CalcWarning::new(
+                        code,
+                        format!(
+                            "{}; {} ({}) was read {}",
+                            cause.describe(),
+                            index.label(),
+                            index.as_str(),
+                            index_rate.source.describe()
+                        ),
+                    ));
                 }
+                index_rate.rate
+            }
+            Err(FetchError::ApiKeyMissing) => return Err(FetchError::ApiKeyMissing.into()),
+            Err(e) if index == RateIndex::Treasury1Year => {
+                log::warn!("Failed to fetch federal rate: {}", e);
+                used_fallback = true;
+                index_sources.push(format!("{} unavailable, default rate used", index.as_str()));
+                let fallback = get_fallback_federal_rate();
+                warnings.push(CalcWarning::new(
+                    WarningCode::DefaultRateUsed,
+                    format!(
+                        "{} could not be fetched ({}); a default of {}% was used",
+                        index.label(),
+                        e,
+                        fallback * Decimal::ONE_HUNDRED
+                    ),
+                ));
+                fallback
             }
-            Ok(None) => return Err(format!("State '{}' not found in database", request.state)),
-            Err(e) => return Err(format!("Database error: {}", e)),
+            Err(e) => {
+                let message = format!("Failed to fetch {} ({})", index.label(), index.as_str());
+                return Err(AppError::from(e).context(message));
+            }
+        };
+        // Indexes are fetched as decimals; formulas are in percent
+        index_values.insert(index, value * Decimal::ONE_HUNDRED);
+    }
+
+    let (rate_percent, rate_steps) = evaluate_rate_formula(&formula, &index_values)
+        .map_err(|e| AppError::new(ErrorCode::InvalidRateFormula, e))?;
+    let rate = rate_percent / Decimal::ONE_HUNDRED;
+    let description = if request.is_federal {
+        if used_fallback {
+            "Federal Rate (Default)".to_string()
+        } else {
+            "Federal Rate (1-Year Treasury)".to_string()
         }
+    } else {
+        let kind = if formula.is_variable() { "Variable" } else { "Fixed" };
+        format!("{} ({}: {})", request.state, kind, formula.describe())
+    };
+    let rate_source = match &jurisdiction {
+        Some(row) => RateSource::new(description, row),
+        None => RateSource {
+            description,
+            citation: FEDERAL_CITATION.to_string(),
+            source_url: FEDERAL_SOURCE_URL.to_string(),
+            effective_date: None,
+            verification_note: String::new(),
+        },
     };
+    if rate_source.citation.is_empty() {
+        warnings.push(CalcWarning::new(
+            WarningCode::MissingCitation,
+            format!(
+                "{} has no statute citation; add one to its rate row before filing",
+                jurisdiction_name
+            ),
+        ));
+    }
+
+    // Payments must fall within the accrual period
+    let mut payments = Vec::with_capacity(request.payments.len());
+    for payment in &request.payments {
+        let date = parse_date(&payment.date, "payment date")?;
+        if date < from_date || date > to_date {
+            return Err(AppError::invalid_input(format!(
+                "Payment on {} falls outside the interest period",
+                payment.date
+            )));
+        }
+        if payment.amount <= Decimal::ZERO {
+            return Err(AppError::invalid_input(format!(
+                "Payment on {} must be a positive amount",
+                payment.date
+            )));
+        }
+        payments.push((date, payment.amount));
+    }
 
-    // Calculate interest
-    let days = calculate_days_between(from_date, to_date);
-    let interest_amount = compute_interest(request.amount, rate, days);
-    let total_amount = request.amount + interest_amount;
+    // Calculate interest, applying payments and capitalizing at each compounding
+    // date of the jurisdiction
+    let days = calculate_days_between(from_date, to_date, day_count);
+    let terms = AccrualTerms {
+        rates: build_rate_schedule(rate, &rate_periods, from_date, to_date),
+        compounding,
+        day_count,
+        rounding,
+        judgment_date,
+    };
+    let accrual = accrue(
+        request.amount,
+        &terms,
+        from_date,
+        to_date,
+        &payments,
+        request.payment_allocation,
+        request.schedule_interval,
+    );
 
     Ok(CalcResponse {
-        rate: (rate * 100.0), // Convert back to percentage for display
+        // Rate in effect on the from date, converted back to percentage for display
+        rate: terms.rates.first().map_or(rate, |(_, r)| *r) * Decimal::ONE_HUNDRED,
         days,
-        interest_amount,
-        total_amount,
+        interest_amount: accrual.total_interest,
+        // Balance still owed at the end date, net of payments
+        total_amount: accrual.balance,
         rate_source,
         disclaimer: "This is an estimate only. Please consult legal advice for accurate calculations. Rates may need manual updates.".to_string(),
+        periods: accrual.periods,
+        day_count,
+        ledger: accrual.ledger,
+        rate_segments: accrual.rate_segments,
+        rate_steps,
+        rate_window: formula
+            .is_variable()
+            .then(|| format!("{} ({})", window.describe(), index_sources.join("; "))),
+        rounding,
+        per_diem: accrual.per_diem,
+        schedule: accrual.schedule,
+        warnings,
     })
 }
 
+// Flags a jurisdiction row that is unverified, overdue for re-verification,
+// lost its index in migration, or contradicts itself
+fn rate_row_warnings(row: &StateRate, today: NaiveDate) -> Vec<CalcWarning> {
+    let mut warnings = Vec::new();
+    match (&row.verified_on, &row.next_review) {
+        (None, _) => warnings.push(CalcWarning::new(
+            WarningCode::UnverifiedRateRow,
+            format!("The {} rate has never been verified against its source", row.state),
+        )),
+        (Some(verified_on), Some(next_review))
+            if NaiveDate::parse_from_str(next_review, "%Y-%m-%d").is_ok_and(|due| due < today) =>
+        {
+            warnings.push(CalcWarning::new(
+                WarningCode::StaleRateRow,
+                format!(
+                    "The {} rate was last verified on {} and was due for re-verification on {}",
+                    row.state, verified_on, next_review
+                ),
+            ))
+        }
+        _ => {}
+    }
+    if row.notes.contains(MISSING_INDEX_NOTE) {
+        warnings.push(CalcWarning::new(
+            WarningCode::MissingRateIndex,
+            format!(
+                "The {} rate is variable but has no index; its last fixed rate was used",
+                row.state
+            ),
+        ));
+    }
+    for issue in lint::lint_row(row) {
+        warnings.push(CalcWarning::new(WarningCode::InconsistentRateRow, issue.message));
+    }
+    warnings
+}
+
+fn parse_date(value: &str, field: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|e| AppError::invalid_date(field, e))
+}
+
+// Converts a stored rate period into the dates and decimal rate the calculator uses
+fn parse_rate_period(
+    period: &StateRatePeriod,
+) -> Result<(NaiveDate, Option<NaiveDate>, Decimal), AppError> {
+    let effective_from = parse_date(&period.effective_from, "rate period start date")?;
+    let effective_to = match &period.effective_to {
+        Some(date) => Some(parse_date(date, "rate period end date")?),
+        None => None,
+    };
+
+    if effective_to.is_some_and(|to| to < effective_from) {
+        return Err(AppError::invalid_input("Rate period must end on or after its start date"));
+    }
+    if period.rate < Decimal::ZERO {
+        return Err(AppError::invalid_input("Rate period rate cannot be negative"));
+    }
+
+    Ok((effective_from, effective_to, period.rate / Decimal::ONE_HUNDRED))
+}
+
 #[tauri::command]
-pub fn get_all_state_rates(app: AppHandle) -> Result<Vec<StateRate>, String> {
-    get_all_states(&app).map_err(|e| format!("Failed to fetch state rates: {}", e))
+pub fn get_all_state_rates(app: AppHandle) -> Result<Vec<StateRate>, AppError> {
+    let conn = get_connection(&app)?;
+    get_all_states(&conn).map_err(|e| AppError::from(e).context("Failed to fetch state rates"))
+}
+
+#[tauri::command]
+pub fn create_state_rate_command(
+    app: AppHandle,
+    state_rate: StateRate,
+    reason: Option<String>,
+) -> Result<StateRate, AppError> {
+    let state_rate = validate_state_rate(StateRate { id: 0, ..state_rate })?;
+    let created = in_transaction(&app, |tx| {
+        if state_name_taken(tx, &state_rate.state, None)? {
+            return Err(duplicate_state(&state_rate.state));
+        }
+        create_state_rate(tx, &state_rate, &audit_context(reason))
+            .map_err(|e| AppError::from(e).context("Failed to create state rate"))
+    });
+    with_lint(&app, created)
 }
 
 #[tauri::command]
 pub fn update_state_rate_command(
     app: AppHandle,
     state_rate: StateRate,
-) -> Result<(), String> {
-    update_state_rate(&app, &state_rate)
-        .map_err(|e| format!("Failed to update state rate: {}", e))
+    reason: Option<String>,
+) -> Result<(), AppError> {
+    let mut state_rate = validate_state_rate(state_rate)?;
+    let updated = in_transaction(&app, |tx| {
+        if state_name_taken(tx, &state_rate.state, Some(state_rate.id))? {
+            return Err(duplicate_state(&state_rate.state));
+        }
+        let current = match get_state_rate_by_id(tx, state_rate.id)? {
+            Some(row) if row.deleted_at.is_some() => {
+                return Err(AppError::invalid_input(format!(
+                    "{} is in the trash; restore it before editing",
+                    row.state
+                )))
+            }
+            Some(row) => row,
+            None => return Err(AppError::new(ErrorCode::NotFound, "State rate not found")),
+        };
+        // A verification vouches for the rate it checked, not a new one
+        if current.formula != state_rate.formula {
+            state_rate.verified_by.clear();
+            state_rate.verified_on = None;
+        }
+        update_state_rate(tx, &state_rate, &audit_context(reason))
+            .map_err(|e| AppError::from(e).context("Failed to update state rate"))
+    });
+    with_lint(&app, updated)
+}
+
+// Trims the name and checks the fields a hand-entered row can get wrong
+fn validate_state_rate(mut state_rate: StateRate) -> Result<StateRate, AppError> {
+    state_rate.state = state_rate.state.trim().to_string();
+    if state_rate.state.is_empty() {
+        return Err(AppError::invalid_input("Jurisdiction name is required"));
+    }
+    state_rate
+        .formula
+        .validate()
+        .map_err(|e| AppError::new(ErrorCode::InvalidRateFormula, e))?;
+
+    state_rate.citation = state_rate.citation.trim().to_string();
+    state_rate.source_url = state_rate.source_url.trim().to_string();
+    state_rate.verification_note = state_rate.verification_note.trim().to_string();
+    let source_url = state_rate.source_url.to_lowercase();
+    if !source_url.is_empty()
+        && !source_url.starts_with("https://")
+        && !source_url.starts_with("http://")
+    {
+        return Err(AppError::invalid_input("Source URL must start with http:// or https://"));
+    }
+    state_rate.effective_date = state_rate
+        .effective_date
+        .map(|date| date.trim().to_string())
+        .filter(|date| !date.is_empty());
+    if let Some(date) = &state_rate.effective_date {
+        parse_date(date, "effective date")?;
+    }
+    if let Some(date) = &state_rate.verified_on {
+        parse_date(date, "verification date")?;
+    }
+    Ok(state_rate)
+}
+
+fn duplicate_state(state: &str) -> AppError {
+    AppError::new(
+        ErrorCode::DuplicateState,
+        format!("A jurisdiction named '{}' already exists", state),
+    )
 }
 
 #[tauri::command]
-pub fn delete_state_rate_command(app: AppHandle, id: i32) -> Result<(), String> {
-    delete_state_rate(&app, id).map_err(|e| format!("Failed to delete state rate: {}", e))
+pub fn delete_state_rate_command(
+    app: AppHandle,
+    id: i32,
+    reason: Option<String>,
+) -> Result<(), AppError> {
+    let deleted = in_transaction(&app, |tx| {
+        delete_state_rate(tx, id, &audit_context(reason))
+            .map_err(|e| AppError::from(e).context("Failed to delete state rate"))
+    });
+    with_lint(&app, deleted)
 }
 
 #[tauri::command]
-pub fn set_api_key(app: AppHandle, api_key: String) -> Result<(), String> {
-    let store_path = app
-        .path()
-        .app_data_dir()
-        .unwrap()
-        .join("config.json");
+pub fn get_trashed_state_rates_command(app: AppHandle) -> Result<Vec<StateRate>, AppError> {
+    get_trashed_states(&app).map_err(|e| AppError::from(e).context("Failed to fetch the trash"))
+}
+
+#[tauri::command]
+pub fn restore_state_rate_command(
+    app: AppHandle,
+    id: i32,
+    reason: Option<String>,
+) -> Result<(), AppError> {
+    let restored = in_transaction(&app, |tx| {
+        restore_state_rate(tx, id, &audit_context(reason))
+            .map_err(|e| AppError::from(e).context("Failed to restore state rate"))
+    });
+    with_lint(&app, restored)
+}
+
+// Only trashed rows can be purged
+#[tauri::command]
+pub fn purge_state_rate_command(
+    app: AppHandle,
+    id: i32,
+    reason: Option<String>,
+) -> Result<(), AppError> {
+    in_transaction(&app, |tx| {
+        match get_state_rate_by_id(tx, id)? {
+            Some(row) if row.deleted_at.is_some() => {}
+            Some(row) => {
+                return Err(AppError::invalid_input(format!(
+                    "{} is not in the trash; delete it before purging",
+                    row.state
+                )))
+            }
+            None => return Err(AppError::new(ErrorCode::NotFound, "State rate not found")),
+        }
+        purge_state_rate(tx, id, &audit_context(reason))
+            .map_err(|e| AppError::from(e).context("Failed to purge state rate"))
+    })
+}
+
+// Stamps the row as checked against its source today by the current user,
+// which sets its next review date
+#[tauri::command]
+pub fn verify_state_rate_command(
+    app: AppHandle,
+    id: i32,
+    note: Option<String>,
+) -> Result<StateRate, AppError> {
+    let context = audit_context(Some("Verified against source".to_string()));
+    in_transaction(&app, |tx| {
+        let state_rate = match get_state_rate_by_id(tx, id)? {
+            Some(row) if row.deleted_at.is_none() => row,
+            _ => return Err(AppError::new(ErrorCode::NotFound, "State rate not found")),
+        };
+        let note = note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty());
+        let verified = StateRate {
+            verified_by: context.changed_by.clone(),
+            verified_on: Some(chrono::Local::now().format("%Y-%m-%d").to_string()),
+            verification_note: note.unwrap_or(state_rate.verification_note.clone()),
+            ..state_rate
+        };
+        update_state_rate(tx, &verified, &context)
+            .map_err(|e| AppError::from(e).context("Failed to verify state rate"))?;
+        Ok(StateRate { next_review: verified.review_due(), ..verified })
+    })
+}
+
+// Rows never verified or past their review date, most overdue first
+#[tauri::command]
+pub fn get_overdue_state_rates_command(app: AppHandle) -> Result<Vec<StateRate>, AppError> {
+    let conn = get_connection(&app)?;
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    get_overdue_states(&conn, &today)
+        .map_err(|e| AppError::from(e).context("Failed to fetch overdue state rates"))
+}
+
+// Rows whose fields contradict each other. The table is also checked at startup
+// and after every edit, with the report sent to the window as LINT_EVENT.
+#[tauri::command]
+pub fn lint_rate_table_command(app: AppHandle) -> Result<LintReport, AppError> {
+    let conn = get_connection(&app)?;
+    let rows = get_all_states(&conn)
+        .map_err(|e| AppError::from(e).context("Failed to fetch state rates"))?;
+    Ok(lint::lint(&rows))
+}
+
+pub const LINT_EVENT: &str = "rate-table-linted";
+
+// Checks the table and sends the report to the window. The write that prompted
+// it has already committed, so a failure here is logged rather than returned.
+pub fn publish_lint(app: &AppHandle) {
+    match lint_rate_table_command(app.clone()) {
+        Ok(report) => {
+            if let Err(e) = app.emit(LINT_EVENT, &report) {
+                log::warn!("Failed to send the rate table check: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to check the rate table: {}", e.message),
+    }
+}
+
+// Publishes a fresh lint report after a write that succeeded
+fn with_lint<T>(app: &AppHandle, written: Result<T, AppError>) -> Result<T, AppError> {
+    if written.is_ok() {
+        publish_lint(app);
+    }
+    written
+}
+
+// Changes to a jurisdiction's row, newest first
+#[tauri::command]
+pub fn get_state_rate_history_command(
+    app: AppHandle,
+    state_rate_id: i32,
+) -> Result<Vec<StateRateAudit>, AppError> {
+    let conn = get_connection(&app)?;
+    get_state_rate_audit(&conn, state_rate_id)
+        .map_err(|e| AppError::from(e).context("Failed to fetch state rate history"))
+}
+
+// Puts a row back as one side of an audit entry recorded it, taking it out of
+// the trash or recreating it if it has since been purged. The revert is itself
+// audited.
+#[tauri::command]
+pub fn revert_state_rate_command(
+    app: AppHandle,
+    audit_id: i64,
+    snapshot: AuditSnapshot,
+    reason: Option<String>,
+) -> Result<StateRate, AppError> {
+    let conn = get_connection(&app)?;
+    let entry = get_audit_entry(&conn, audit_id)?.ok_or_else(|| {
+        AppError::new(ErrorCode::NotFound, format!("Audit entry {} not found", audit_id))
+    })?;
+    let (target, periods) = match snapshot {
+        AuditSnapshot::Before => (entry.before, entry.before_periods),
+        AuditSnapshot::After => (entry.after, entry.after_periods),
+    };
+    let target = target.ok_or_else(|| {
+        AppError::invalid_input(format!(
+            "Audit entry {} has no {} snapshot",
+            audit_id,
+            if snapshot == AuditSnapshot::Before { "before" } else { "after" }
+        ))
+    })?;
+
+    let reverted = format!("Reverted to audit entry {}", audit_id);
+    let reason = match reason.as_deref().map(str::trim) {
+        Some(reason) if !reason.is_empty() => format!("{}: {}", reverted, reason),
+        _ => reverted,
+    };
+    let context = audit_context(Some(reason));
+
+    let reverted = in_transaction(&app, |tx| {
+        if state_name_taken(tx, &target.state, Some(target.id))? {
+            return Err(duplicate_state(&target.state));
+        }
+        Ok(revert_state_rate(tx, &target, periods.as_deref(), &context)?)
+    });
+    with_lint(&app, reverted)
+}
+
+// Runs the writes on one connection, committing only if all of them succeed
+fn in_transaction<T>(
+    app: &AppHandle,
+    writes: impl FnOnce(&Connection) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let mut conn = get_connection(app)?;
+    let tx = conn.transaction()?;
+    let value = writes(&tx)?;
+    tx.commit()?;
+    Ok(value)
+}
+
+// The OS user making the change; blank reasons are not recorded
+fn audit_context(reason: Option<String>) -> AuditContext {
+    let changed_by = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string());
+    let reason = reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty());
+    AuditContext { changed_by, reason }
+}
+
+// The current table with each state's rate periods, as export files carry it
+fn current_rows(conn: &Connection) -> Result<Vec<(StateRate, RateTableRow)>, AppError> {
+    get_all_states(conn)?
+        .into_iter()
+        .map(|state_rate| {
+            let periods = get_rate_periods(conn, state_rate.id)?;
+            let row = RateTableRow::new(&state_rate, &periods);
+            Ok((state_rate, row))
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn export_rate_table_command(app: AppHandle, format: TableFormat) -> Result<String, AppError> {
+    let conn = get_connection(&app)?;
+    let rows: Vec<RateTableRow> = current_rows(&conn)?.into_iter().map(|(_, row)| row).collect();
+    export_rows(&rows, format).map_err(|e| {
+        AppError::new(ErrorCode::InvalidInput, "Failed to export the rate table").with_details(e)
+    })
+}
+
+// Parses and validates every row of an import file
+fn parse_import(contents: &str, format: TableFormat) -> Result<Vec<RateTableRow>, AppError> {
+    let invalid = |e: String| {
+        AppError::new(ErrorCode::InvalidImport, "The import file is not valid").with_details(e)
+    };
+    let rows = parse_rows(contents, format).map_err(invalid)?;
+    rows.into_iter()
+        .map(|row| {
+            let (state_rate, periods) = row.to_state_rate(0);
+            let checked = validate_state_rate(state_rate).and_then(|state_rate| {
+                for period in &periods {
+                    parse_rate_period(period)?;
+                }
+                Ok(state_rate)
+            });
+            let state_rate =
+                checked.map_err(|e| invalid(format!("{}: {}", row.state, e.message)))?;
+            Ok(RateTableRow { state: state_rate.state, ..row })
+        })
+        .collect()
+}
+
+// What importing the file would add, change and move to the trash
+#[tauri::command]
+pub fn preview_rate_table_import_command(
+    app: AppHandle,
+    format: TableFormat,
+    contents: String,
+) -> Result<ImportPreview, AppError> {
+    let incoming = parse_import(&contents, format)?;
+    let conn = get_connection(&app)?;
+    let current: Vec<RateTableRow> =
+        current_rows(&conn)?.into_iter().map(|(_, row)| row).collect();
+    Ok(diff_rows(&current, &incoming))
+}
+
+// Makes the table match the file in one transaction: new states are added,
+// changed ones updated with their rate periods replaced, and states missing
+// from the file moved to the trash. Every change is audited.
+#[tauri::command]
+pub fn import_rate_table_command(
+    app: AppHandle,
+    format: TableFormat,
+    contents: String,
+    reason: Option<String>,
+) -> Result<ImportPreview, AppError> {
+    let incoming = parse_import(&contents, format)?;
+    let context = audit_context(Some(reason.unwrap_or_else(|| "Imported from file".to_string())));
+
+    let imported = in_transaction(&app, |tx| {
+        let current = current_rows(tx)?;
+        let rows: Vec<RateTableRow> = current.iter().map(|(_, row)| row.clone()).collect();
+        let preview = diff_rows(&rows, &incoming);
+        let id_of = |state: &str| {
+            current
+                .iter()
+                .find(|(state_rate, _)| state_rate.state == state)
+                .map(|(state_rate, _)| state_rate.id)
+        };
+
+        for row in &incoming {
+            if !preview.added.contains(&row.state) {
+                continue;
+            }
+            if state_name_taken(tx, &row.state, None)? {
+                return Err(AppError::new(
+                    ErrorCode::DuplicateState,
+                    format!("'{}' is in the trash; restore or purge it first", row.state),
+                ));
+            }
+            let (state_rate, periods) = row.to_state_rate(0);
+            let created = create_state_rate(tx, &state_rate, &context)?;
+            replace_rate_periods(tx, created.id, &periods, &context)?;
+        }
+        for change in &preview.changed {
+            let id = id_of(&change.state).expect("changed rows exist in the table");
+            let row = incoming
+                .iter()
+                .find(|row| row.state.eq_ignore_ascii_case(&change.state))
+                .expect("changed rows come from the file");
+            let (mut state_rate, periods) = row.to_state_rate(id);
+            let changed = |name: &str| change.fields.iter().any(|field| field == name);
+            // As with a hand edit, a new formula drops a verification the file left as it was
+            if changed("formula") && !changed("verified_by") && !changed("verified_on") {
+                state_rate.verified_by.clear();
+                state_rate.verified_on = None;
+            }
+            update_state_rate(tx, &state_rate, &context)?;
+            if changed("rate_periods") {
+                replace_rate_periods(tx, id, &periods, &context)?;
+            }
+        }
+        for state in &preview.removed {
+            let id = id_of(state).expect("removed rows exist in the table");
+            delete_state_rate(tx, id, &context)?;
+        }
+        Ok(preview)
+    })
+    .map_err(|e| {
+        let message = format!("Import failed; nothing was changed: {}", e.message);
+        e.context(message)
+    });
+    with_lint(&app, imported)
+}
+
+// Shipped rate updates held back because the user had edited the row
+#[tauri::command]
+pub fn get_seed_conflicts_command(app: AppHandle) -> Result<Vec<SeedConflict>, AppError> {
+    let conn = get_connection(&app)?;
+    seed::conflicts(&conn, &seed::shipped())
+        .map_err(|e| AppError::from(e).context("Failed to compare with the shipped rate table"))
+}
+
+// Takes the shipped values for the row or keeps the local ones
+#[tauri::command]
+pub fn resolve_seed_conflict_command(
+    app: AppHandle,
+    state: String,
+    accept_shipped: bool,
+    reason: Option<String>,
+) -> Result<(), AppError> {
+    let resolved = in_transaction(&app, |tx| {
+        let context = audit_context(reason);
+        let shipped = seed::shipped();
+        let resolved = seed::resolve_conflict(tx, &shipped, &state, accept_shipped, &context)
+            .map_err(|e| AppError::from(e).context("Failed to resolve the seed conflict"))?;
+        if !resolved {
+            return Err(AppError::new(
+                ErrorCode::NotFound,
+                format!("No shipped update to {} is awaiting review", state),
+            ));
+        }
+        Ok(())
+    });
+    with_lint(&app, resolved)
+}
+
+// Puts the named states, or the whole table, back to the shipped values after
+// exporting the current table next to rates.db, where Import can restore it
+#[tauri::command]
+pub fn reset_rate_table_command(
+    app: AppHandle,
+    states: Option<Vec<String>>,
+    reason: Option<String>,
+) -> Result<ResetReport, AppError> {
+    let shipped = seed::shipped();
+    if let Some(states) = &states {
+        if states.is_empty() {
+            return Err(AppError::invalid_input("Choose at least one state to reset"));
+        }
+        let unknown = states.iter().find(|state| {
+            !shipped.state_rates.iter().any(|row| row.state.eq_ignore_ascii_case(state))
+        });
+        if let Some(state) = unknown {
+            return Err(AppError::invalid_input(format!(
+                "{} is not in the shipped rate table",
+                state
+            )));
+        }
+    }
+
+    let backup = get_db_path(&app).with_file_name(format!(
+        "state-rates-before-reset-{}.json",
+        chrono::Local::now().format("%Y%m%dT%H%M%S")
+    ));
+    let contents = export_rate_table_command(app.clone(), TableFormat::Json)?;
+    std::fs::write(&backup, contents).map_err(|e| {
+        AppError::new(
+            ErrorCode::DatabaseError,
+            "Failed to back up the rate table before resetting it",
+        )
+        .with_details(e.to_string())
+    })?;
+
+    let context = audit_context(Some(reason.unwrap_or_else(|| {
+        format!("Reset to shipped rate table v{}", shipped.version)
+    })));
+    let mut report = in_transaction(&app, |tx| {
+        seed::reset(tx, &shipped, states.as_deref(), &context)
+            .map_err(|e| AppError::from(e).context("Failed to reset the rate table"))
+    })?;
+    report.backup = backup.to_string_lossy().into_owned();
+    publish_lint(&app);
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn get_rate_periods_command(
+    app: AppHandle,
+    state_id: i32,
+) -> Result<Vec<StateRatePeriod>, AppError> {
+    let conn = get_connection(&app)?;
+    get_rate_periods(&conn, state_id)
+        .map_err(|e| AppError::from(e).context("Failed to fetch rate periods"))
+}
+
+// Rate period edits are audited against their row like edits of the row itself
+#[tauri::command]
+pub fn add_rate_period_command(
+    app: AppHandle,
+    period: StateRatePeriod,
+    reason: Option<String>,
+) -> Result<i64, AppError> {
+    parse_rate_period(&period)?;
+    in_transaction(&app, |tx| {
+        create_rate_period(tx, &period, &audit_context(reason))
+            .map_err(|e| AppError::from(e).context("Failed to add rate period"))
+    })
+}
+
+#[tauri::command]
+pub fn update_rate_period_command(
+    app: AppHandle,
+    period: StateRatePeriod,
+    reason: Option<String>,
+) -> Result<(), AppError> {
+    parse_rate_period(&period)?;
+    in_transaction(&app, |tx| {
+        update_rate_period(tx, &period, &audit_context(reason))
+            .map_err(|e| AppError::from(e).context("Failed to update rate period"))
+    })
+}
+
+#[tauri::command]
+pub fn delete_rate_period_command(
+    app: AppHandle,
+    id: i32,
+    reason: Option<String>,
+) -> Result<(), AppError> {
+    in_transaction(&app, |tx| {
+        delete_rate_period(tx, id, &audit_context(reason))
+            .map_err(|e| AppError::from(e).context("Failed to delete rate period"))
+    })
+}
+
+// Trims the text fields and checks the ones a calculation depends on
+fn validate_matter(mut matter: Matter) -> Result<Matter, AppError> {
+    for field in [
+        &mut matter.caption,
+        &mut matter.docket_number,
+        &mut matter.court,
+        &mut matter.client,
+        &mut matter.jurisdiction,
+    ] {
+        *field = field.trim().to_string();
+    }
+    if matter.caption.is_empty() {
+        return Err(AppError::invalid_input("Case caption is required"));
+    }
+    if matter.jurisdiction.is_empty() {
+        return Err(AppError::invalid_input("Jurisdiction is required"));
+    }
+    parse_date(&matter.judgment_date, "judgment date")?;
+    if matter.principal < Decimal::ZERO {
+        return Err(AppError::invalid_input("Principal cannot be negative"));
+    }
+    Ok(matter)
+}
+
+fn matter_not_found() -> AppError {
+    AppError::new(ErrorCode::NotFound, "Matter not found")
+}
+
+fn calculation_not_found() -> AppError {
+    AppError::new(ErrorCode::NotFound, "Saved calculation not found")
+}
+
+fn calculation_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_input("Calculation name is required"));
+    }
+    Ok(name.to_string())
+}
+
+// Most recently changed first, with how many calculations each has
+#[tauri::command]
+pub fn get_matters_command(app: AppHandle) -> Result<Vec<Matter>, AppError> {
+    let conn = get_connection(&app)?;
+    get_matters(&conn).map_err(|e| AppError::from(e).context("Failed to fetch matters"))
+}
+
+#[tauri::command]
+pub fn create_matter_command(app: AppHandle, matter: Matter) -> Result<Matter, AppError> {
+    let matter = validate_matter(matter)?;
+    in_transaction(&app, |tx| {
+        let id = insert_matter(tx, &matter)
+            .map_err(|e| AppError::from(e).context("Failed to create matter"))?;
+        get_matter(tx, id)?.ok_or_else(matter_not_found)
+    })
+}
+
+// Edits the case details, including renaming the matter
+#[tauri::command]
+pub fn update_matter_command(app: AppHandle, matter: Matter) -> Result<Matter, AppError> {
+    let matter = validate_matter(matter)?;
+    in_transaction(&app, |tx| {
+        if get_matter(tx, matter.id)?.is_none() {
+            return Err(matter_not_found());
+        }
+        update_matter(tx, &matter)
+            .map_err(|e| AppError::from(e).context("Failed to update matter"))?;
+        get_matter(tx, matter.id)?.ok_or_else(matter_not_found)
+    })
+}
+
+// Deletes the matter and every calculation saved against it
+#[tauri::command]
+pub fn delete_matter_command(app: AppHandle, id: i64) -> Result<(), AppError> {
+    in_transaction(&app, |tx| {
+        delete_matter(tx, id).map_err(|e| AppError::from(e).context("Failed to delete matter"))
+    })
+}
+
+// Newest first, without their full results
+#[tauri::command]
+pub fn get_calculations_command(
+    app: AppHandle,
+    matter_id: i64,
+) -> Result<Vec<CalculationSummary>, AppError> {
+    let conn = get_connection(&app)?;
+    get_calculations(&conn, matter_id)
+        .map_err(|e| AppError::from(e).context("Failed to fetch saved calculations"))
+}
+
+// Stores a result as shown, so a saved calculation reproduces what was filed
+// even after rates or the rate table change
+#[tauri::command]
+pub fn save_calculation_command(
+    app: AppHandle,
+    matter_id: i64,
+    name: String,
+    request: CalcRequest,
+    response: CalcResponse,
+) -> Result<i64, AppError> {
+    let name = calculation_name(&name)?;
+    in_transaction(&app, |tx| {
+        if get_matter(tx, matter_id)?.is_none() {
+            return Err(matter_not_found());
+        }
+        insert_calculation(tx, matter_id, &name, &request, &response)
+            .map_err(|e| AppError::from(e).context("Failed to save calculation"))
+    })
+}
+
+#[tauri::command]
+pub fn get_calculation_command(app: AppHandle, id: i64) -> Result<SavedCalculation, AppError> {
+    let conn = get_connection(&app)?;
+    get_calculation(&conn, id)
+        .map_err(|e| AppError::from(e).context("Failed to open saved calculation"))?
+        .ok_or_else(calculation_not_found)
+}
+
+#[tauri::command]
+pub fn rename_calculation_command(app: AppHandle, id: i64, name: String) -> Result<(), AppError> {
+    let name = calculation_name(&name)?;
+    in_transaction(&app, |tx| {
+        if get_calculation(tx, id)?.is_none() {
+            return Err(calculation_not_found());
+        }
+        rename_calculation(tx, id, &name)
+            .map_err(|e| AppError::from(e).context("Failed to rename calculation"))
+    })
+}
+
+#[tauri::command]
+pub fn delete_calculation_command(app: AppHandle, id: i64) -> Result<(), AppError> {
+    in_transaction(&app, |tx| {
+        delete_calculation(tx, id)
+            .map_err(|e| AppError::from(e).context("Failed to delete calculation"))
+    })
+}
+
+// Calculates a saved request again against the current rates, optionally
+// through a new end date, and saves the result beside the original
+#[tauri::command]
+pub fn rerun_calculation_command(
+    app: AppHandle,
+    id: i64,
+    to_date: Option<String>,
+) -> Result<SavedCalculation, AppError> {
+    let conn = get_connection(&app)?;
+    let saved = get_calculation(&conn, id)?.ok_or_else(calculation_not_found)?;
+    let request =
+        CalcRequest { to_date: to_date.unwrap_or(saved.request.to_date), ..saved.request };
+    let response = calculate(app.clone(), request.clone())?;
+    let id = in_transaction(&app, |tx| {
+        if get_matter(tx, saved.matter_id)?.is_none() {
+            return Err(matter_not_found());
+        }
+        insert_calculation(tx, saved.matter_id, &saved.name, &request, &response)
+            .map_err(|e| AppError::from(e).context("Failed to save calculation"))
+    })?;
+    get_calculation(&conn, id)?.ok_or_else(calculation_not_found)
+}
+
+#[tauri::command]
+pub fn set_api_key(app: AppHandle, api_key: String) -> Result<(), AppError> {
+    let store_path = config_path(&app)?;
     
     let config = serde_json::json!({
         "fred_api_key": api_key
     });
     
     std::fs::write(&store_path, serde_json::to_string_pretty(&config).unwrap())
-        .map_err(|e| format!("Failed to save API key: {}", e))?;
+        .map_err(|e| {
+            AppError::new(ErrorCode::ConfigError, "Failed to save API key")
+                .with_details(e.to_string())
+        })?;
     
     Ok(())
 }
 
 #[tauri::command]
-pub fn get_api_key_configured(app: AppHandle) -> Result<bool, String> {
+pub fn get_api_key_configured(app: AppHandle) -> Result<bool, AppError> {
     match get_api_key(&app) {
         Ok(key) => Ok(!key.is_empty()),
         Err(_) => Ok(false),
     }
 }
 
-// Helper function to get API key from config
-fn get_api_key(app: &AppHandle) -> Result<String, String> {
-    let store_path = app
-        .path()
+fn config_path(app: &AppHandle) -> Result<std::path::PathBuf, AppError> {
+    app.path()
         .app_data_dir()
-        .unwrap()
-        .join("config.json");
+        .map(|dir| dir.join("config.json"))
+        .map_err(|e| {
+            AppError::new(ErrorCode::ConfigError, "App data directory unavailable")
+                .with_details(e.to_string())
+        })
+}
+
+// Helper function to get API key from config
+fn get_api_key(app: &AppHandle) -> Result<String, AppError> {
+    let store_path = config_path(app)?;
     
     if !store_path.exists() {
-        return Err("API key not configured. Please set it in the settings.".to_string());
+        return Err(FetchError::ApiKeyMissing.into());
     }
     
-    let content = std::fs::read_to_string(&store_path)
-        .map_err(|e| format!("Failed to read config: {}", e))?;
+    let content = std::fs::read_to_string(&store_path).map_err(|e| {
+        AppError::new(ErrorCode::ConfigError, "Failed to read config").with_details(e.to_string())
+    })?;
     
-    let config: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse config: {}", e))?;
+    let config: serde_json::Value = serde_json::from_str(&content).map_err(|e| {
+        AppError::new(ErrorCode::ConfigError, "Failed to parse config").with_details(e.to_string())
+    })?;
     
     match config.get("fred_api_key") {
         Some(serde_json::Value::String(key)) => Ok(key.clone()),
-        _ => Err("API key not found in config".to_string()),
+        _ => Err(FetchError::ApiKeyMissing.into()),
     }
 }
 
 #[tauri::command]
-pub fn validate_api_key_command(api_key: String) -> Result<bool, String> {
+pub fn validate_api_key_command(api_key: String) -> Result<bool, AppError> {
     use crate::rate_fetcher::validate_api_key;
     
-    match validate_api_key(&api_key) {
-        Ok(valid) => Ok(valid),
-        Err(e) => Err(format!("Failed to validate API key: {}", e)),
-    }
+    validate_api_key(&api_key)
+        .map_err(|e| AppError::from(e).context("Failed to validate API key"))
 }
@@ -1,6 +1,16 @@
-use crate::models::StateRate;
-use rusqlite::{Connection, OptionalExtension, Result, params};
-use std::path::PathBuf;
+use crate::error::{AppError, ErrorCode};
+use crate::models::{
+    AuditAction, AuditContext, CalcRequest, CalcResponse, CalculationSummary, CompoundingMode,
+    DayCount, FredObservation, Matter, RateDateRule, RateFormula, RateIndex, RoundingMode,
+    RoundingPolicy, RoundingStage, SavedCalculation, StateRate, StateRateAudit, StateRatePeriod,
+    UpdateFrequency,
+};
+use crate::seed::{self, SeedBaseline, SeedRow};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef};
+use rusqlite::{Connection, OptionalExtension, Result, Row, ToSql, params};
+use rust_decimal::Decimal;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use tauri::{AppHandle, Manager};
 
 pub fn get_db_path(app: &AppHandle) -> PathBuf {
@@ -13,188 +23,1809 @@ pub fn get_db_path(app: &AppHandle) -> PathBuf {
     path
 }
 
-pub fn init_db(app: &AppHandle) -> Result<()> {
-    let db_path = get_db_path(app);
-    let conn = Connection::open(db_path)?;
+// Appended to the notes of a legacy variable rate that named no index
+pub const MISSING_INDEX_NOTE: &str = "[Variable rate with no index; migrated as fixed]";
 
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS state_rates (
+// Decimal columns are TEXT so rates round-trip exactly; rate_formula is JSON.
+// This is the table as of schema version 1; later columns are added by migrations.
+const STATE_RATES_COLUMNS: &str = "(
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             state TEXT NOT NULL UNIQUE,
-            rate REAL NOT NULL,
-            is_variable INTEGER NOT NULL,
-            plus_percentage REAL NOT NULL,
+            rate_formula TEXT NOT NULL,
             update_frequency TEXT NOT NULL,
             last_update TEXT NOT NULL,
+            notes TEXT,
+            compounding TEXT NOT NULL DEFAULT 'none',
+            day_count TEXT NOT NULL DEFAULT 'actual_365',
+            rounding_stage TEXT NOT NULL DEFAULT 'per_period',
+            rounding_mode TEXT NOT NULL DEFAULT 'half_up',
+            rate_date_rule TEXT NOT NULL DEFAULT 'preceding_week'
+        )";
+
+// Effective-dated rate history; effective_to is inclusive and NULL while current
+const STATE_RATE_PERIODS_COLUMNS: &str = "(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            state_id INTEGER NOT NULL REFERENCES state_rates(id),
+            effective_from TEXT NOT NULL,
+            effective_to TEXT,
+            rate TEXT NOT NULL,
             notes TEXT
-        )",
+        )";
+
+// Every insert, update and delete of a state_rates row, with JSON snapshots of
+// the row before and after
+const STATE_RATE_AUDIT_COLUMNS: &str = "(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            state_rate_id INTEGER NOT NULL,
+            state TEXT NOT NULL,
+            action TEXT NOT NULL,
+            before TEXT,
+            after TEXT,
+            changed_at TEXT NOT NULL,
+            changed_by TEXT NOT NULL,
+            reason TEXT
+        )";
+
+// The shipped values each seeded row was last brought in line with, keyed by
+// the shipped name
+const SEED_BASELINES_COLUMNS: &str = "(
+            state TEXT PRIMARY KEY COLLATE NOCASE,
+            state_rate_id INTEGER REFERENCES state_rates(id) ON DELETE SET NULL,
+            seed_version INTEGER NOT NULL,
+            shipped TEXT NOT NULL
+        )";
+
+// Judgments being tracked; principal is TEXT like the rate columns
+const MATTERS_COLUMNS: &str = "(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            caption TEXT NOT NULL,
+            docket_number TEXT NOT NULL DEFAULT '',
+            court TEXT NOT NULL DEFAULT '',
+            client TEXT NOT NULL DEFAULT '',
+            judgment_date TEXT NOT NULL,
+            principal TEXT NOT NULL,
+            jurisdiction TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )";
+
+// Calculations saved against a matter, with the request and response as JSON
+const CALCULATIONS_COLUMNS: &str = "(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            matter_id INTEGER NOT NULL REFERENCES matters(id),
+            name TEXT NOT NULL,
+            request TEXT NOT NULL,
+            response TEXT NOT NULL,
+            calculated_at TEXT NOT NULL
+        )";
+
+// FRED observations as published; value is "." where FRED has none for the date
+const FRED_OBSERVATIONS_COLUMNS: &str = "(
+            series_id TEXT NOT NULL,
+            date TEXT NOT NULL,
+            value TEXT NOT NULL,
+            realtime_start TEXT NOT NULL,
+            realtime_end TEXT NOT NULL,
+            PRIMARY KEY (series_id, date)
+        )";
+
+// Date ranges already fetched for each series, so a range with no observations
+// (weekends, holidays) is known to be complete rather than missing
+const FRED_COVERAGE_COLUMNS: &str = "(
+            series_id TEXT NOT NULL,
+            start_date TEXT NOT NULL,
+            end_date TEXT NOT NULL,
+            fetched_at TEXT NOT NULL
+        )";
+
+pub fn init_db(app: &AppHandle) -> std::result::Result<(), AppError> {
+    let db_path = get_db_path(app);
+    let conn = Connection::open(&db_path)?;
+
+    migrate(&conn, Some(&db_path))?;
+
+    let tx = conn.unchecked_transaction()?;
+    let report = seed::sync(&tx, &seed::shipped())
+        .map_err(|e| AppError::from(e).context("Failed to apply the shipped rate table"))?;
+    tx.commit()?;
+    if !report.added.is_empty() || !report.updated.is_empty() {
+        log::info!(
+            "Seed data v{}: added {:?}, updated {:?}",
+            report.seed_version,
+            report.added,
+            report.updated
+        );
+    }
+    if !report.conflicts.is_empty() {
+        log::warn!(
+            "Seed data v{}: {} edited rows differ from the shipped values",
+            report.seed_version,
+            report.conflicts.len()
+        );
+    }
+
+    Ok(())
+}
+
+// Schema changes in order; migration N brings a database from user_version N - 1
+// to N. Append new steps, never edit or reorder released ones.
+const MIGRATIONS: &[fn(&Connection) -> Result<()>] = &[
+    migrate_baseline,
+    migrate_audit,
+    migrate_soft_delete,
+    migrate_seed_baselines,
+    migrate_rate_sources,
+    migrate_verification,
+    migrate_matters,
+    migrate_period_audit,
+];
+
+pub const SCHEMA_VERSION: i32 = MIGRATIONS.len() as i32;
+
+// Brings the database up to SCHEMA_VERSION, copying it next to `db_path` first.
+// Each step commits with its version number, so an interrupted upgrade resumes
+// from the last completed step.
+pub fn migrate(conn: &Connection, db_path: Option<&Path>) -> std::result::Result<(), AppError> {
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > SCHEMA_VERSION {
+        return Err(AppError::new(
+            ErrorCode::SchemaTooNew,
+            "The rate database was written by a newer version of this app; update the app to open it",
+        )
+        .with_details(format!(
+            "Database schema version {}, this version supports up to {}",
+            version, SCHEMA_VERSION
+        )));
+    }
+    if version == SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    // A database with no tables is new and has nothing to back up
+    let table_count: i32 =
+        conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'", [], |row| {
+            row.get(0)
+        })?;
+    if let Some(db_path) = db_path.filter(|_| table_count > 0) {
+        let backup = backup_path(db_path, version);
+        conn.execute("VACUUM INTO ?1", [backup.to_string_lossy()]).map_err(|e| {
+            AppError::from(e).context("Failed to back up the rate database before upgrading it")
+        })?;
+        log::info!("Backed up rates.db to {}", backup.display());
+    }
+
+    // Table rebuilds drop tables others reference; the pragma is a no-op inside
+    // a transaction, so it is set around them and the keys are checked before
+    // each step commits
+    conn.execute_batch("PRAGMA foreign_keys = OFF")?;
+    if !table_columns(conn, "seed_baselines")?.is_empty() {
+        unlink_seed_baselines(conn)?;
+    }
+    let result = (version..SCHEMA_VERSION).try_for_each(|applied| {
+        let tx = conn.unchecked_transaction()?;
+        MIGRATIONS[applied as usize](&tx)?;
+        let violations: i32 =
+            tx.query_row("SELECT COUNT(*) FROM pragma_foreign_key_check", [], |row| row.get(0))?;
+        if violations > 0 {
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT_FOREIGNKEY),
+                Some(format!("Migration {} left {} dangling references", applied + 1, violations)),
+            ));
+        }
+        tx.pragma_update(None, "user_version", applied + 1)?;
+        tx.commit()
+    });
+    conn.execute_batch("PRAGMA foreign_keys = ON")?;
+    result.map_err(|e| AppError::from(e).context("Failed to upgrade the rate database"))
+}
+
+// rates.db.v3-20240101T120000.bak beside rates.db
+fn backup_path(db_path: &Path, version: i32) -> PathBuf {
+    let file_name = db_path.file_name().map_or("rates.db".into(), |name| name.to_string_lossy());
+    db_path.with_file_name(format!(
+        "{}.v{}-{}.bak",
+        file_name,
+        version,
+        chrono::Local::now().format("%Y%m%dT%H%M%S")
+    ))
+}
+
+// Version 1: the schema as it stood when migrations were introduced. Databases
+// from before then were upgraded piecemeal on every start, so this brings any of
+// them, or an empty database, to the same tables.
+fn migrate_baseline(conn: &Connection) -> Result<()> {
+    conn.execute(&format!("CREATE TABLE IF NOT EXISTS state_rates {}", STATE_RATES_COLUMNS), [])?;
+
+    // Databases created before compounding was tracked get the column added, with
+    // the jurisdictions whose statutes compound brought in line with their notes
+    if add_column_if_missing(conn, "state_rates", "compounding", "TEXT NOT NULL DEFAULT 'none'")? {
+        conn.execute(
+            "UPDATE state_rates SET compounding = 'anniversary' WHERE state = 'Federal'",
+            [],
+        )?;
+        conn.execute(
+            "UPDATE state_rates SET compounding = 'annual' WHERE notes LIKE '%compounded annually%'",
+            [],
+        )?;
+    }
+
+    add_column_if_missing(conn, "state_rates", "day_count", "TEXT NOT NULL DEFAULT 'actual_365'")?;
+    add_column_if_missing(
+        conn,
+        "state_rates",
+        "rounding_stage",
+        "TEXT NOT NULL DEFAULT 'per_period'",
+    )?;
+    add_column_if_missing(
+        conn,
+        "state_rates",
+        "rounding_mode",
+        "TEXT NOT NULL DEFAULT 'half_up'",
+    )?;
+    add_column_if_missing(
+        conn,
+        "state_rates",
+        "rate_date_rule",
+        "TEXT NOT NULL DEFAULT 'preceding_week'",
+    )?;
+
+    // Rates were a rate / is_variable / plus_percentage triple before they
+    // became formulas
+    if table_columns(conn, "state_rates")?.iter().any(|(name, _)| name == "is_variable") {
+        // Variable rates used to float on the 1-year Treasury whatever their
+        // statute said; name the index each one's notes describe
+        if add_column_if_missing(conn, "state_rates", "rate_index", "TEXT")? {
+            conn.execute(
+                "UPDATE state_rates SET rate_index = 'DPRIME'
+                 WHERE is_variable = 1 AND notes LIKE '%Prime%'",
+                [],
+            )?;
+            conn.execute(
+                "UPDATE state_rates SET rate_index = 'DGS1'
+                 WHERE is_variable = 1 AND rate_index IS NULL
+                   AND (state = 'Federal' OR notes LIKE '%Federal%' OR notes LIKE '%Treasury%')",
+                [],
+            )?;
+        }
+
+        convert_rate_formulas(conn)?;
+    }
+
+    conn.execute(
+        &format!("CREATE TABLE IF NOT EXISTS state_rate_periods {}", STATE_RATE_PERIODS_COLUMNS),
         [],
     )?;
 
-    // Check if we need to seed initial data
-    let count: i32 = conn.query_row("SELECT COUNT(*) FROM state_rates", [], |row| row.get(0))?;
-    
-    if count == 0 {
-        seed_initial_data(&conn)?;
-    }
-
-    Ok(())
-}
-
-fn seed_initial_data(conn: &Connection) -> Result<()> {
-    let initial_states = vec![
-        // Federal rate is variable
-        StateRate::new("Federal".to_string(), 0.0, true, 0.0, "Weekly".to_string(), "Based on 1-year Treasury yield".to_string()),
-        
-        // State rates (simplified - in production, research actual rates)
-        StateRate::new("Alabama".to_string(), 7.5, false, 0.0, "Annual".to_string(), "7.5% per annum".to_string()),
-        StateRate::new("Alaska".to_string(), 3.0, true, 0.0, "Annual".to_string(), "Federal rate + 3%".to_string()),
-        StateRate::new("Arizona".to_string(), 10.0, false, 0.0, "Annual".to_string(), "10% per annum".to_string()),
-        StateRate::new("Arkansas".to_string(), 8.0, false, 0.0, "Annual".to_string(), "8% per annum".to_string()),
-        StateRate::new("California".to_string(), 10.0, false, 0.0, "Annual".to_string(), "10% per annum".to_string()),
-        StateRate::new("Colorado".to_string(), 8.0, false, 0.0, "Annual".to_string(), "8% per annum compounded annually".to_string()),
-        StateRate::new("Connecticut".to_string(), 8.0, false, 0.0, "Annual".to_string(), "8% per annum".to_string()),
-        StateRate::new("Delaware".to_string(), 0.0, true, 5.0, "Annual".to_string(), "Federal rate + 5%".to_string()),
-        StateRate::new("District of Columbia".to_string(), 6.0, false, 0.0, "Annual".to_string(), "6% per annum".to_string()),
-        StateRate::new("Florida".to_string(), 4.75, false, 0.0, "Annual".to_string(), "4.75% per annum as of 2024".to_string()),
-        StateRate::new("Georgia".to_string(), 7.0, false, 0.0, "Annual".to_string(), "7% per annum".to_string()),
-        StateRate::new("Hawaii".to_string(), 10.0, false, 0.0, "Annual".to_string(), "10% per annum".to_string()),
-        StateRate::new("Idaho".to_string(), 12.0, false, 0.0, "Annual".to_string(), "12% per annum".to_string()),
-        StateRate::new("Illinois".to_string(), 9.0, false, 0.0, "Annual".to_string(), "9% per annum".to_string()),
-        StateRate::new("Indiana".to_string(), 8.0, false, 0.0, "Annual".to_string(), "8% per annum".to_string()),
-        StateRate::new("Iowa".to_string(), 5.0, true, 2.0, "Annual".to_string(), "Federal rate + 2%".to_string()),
-        StateRate::new("Kansas".to_string(), 10.0, false, 0.0, "Annual".to_string(), "10% per annum".to_string()),
-        StateRate::new("Kentucky".to_string(), 8.0, false, 0.0, "Annual".to_string(), "8% per annum".to_string()),
-        StateRate::new("Louisiana".to_string(), 3.5, false, 0.0, "Annual".to_string(), "3.5% per annum".to_string()),
-        StateRate::new("Maine".to_string(), 8.0, false, 0.0, "Annual".to_string(), "8% per annum".to_string()),
-        StateRate::new("Maryland".to_string(), 10.0, false, 0.0, "Annual".to_string(), "10% per annum".to_string()),
-        StateRate::new("Massachusetts".to_string(), 12.0, false, 0.0, "Annual".to_string(), "12% per annum".to_string()),
-        StateRate::new("Michigan".to_string(), 5.25, false, 0.0, "Semi-Annual".to_string(), "5.25% per annum".to_string()),
-        StateRate::new("Minnesota".to_string(), 10.0, false, 0.0, "Annual".to_string(), "10% per annum".to_string()),
-        StateRate::new("Mississippi".to_string(), 8.0, false, 0.0, "Annual".to_string(), "8% per annum".to_string()),
-        StateRate::new("Missouri".to_string(), 9.0, false, 0.0, "Annual".to_string(), "9% per annum".to_string()),
-        StateRate::new("Montana".to_string(), 10.0, false, 0.0, "Annual".to_string(), "10% per annum".to_string()),
-        StateRate::new("Nebraska".to_string(), 12.0, false, 0.0, "Annual".to_string(), "12% per annum".to_string()),
-        StateRate::new("Nevada".to_string(), 5.25, false, 0.0, "Annual".to_string(), "Prime rate + 2%".to_string()),
-        StateRate::new("New Hampshire".to_string(), 7.0, false, 0.0, "Annual".to_string(), "7% per annum".to_string()),
-        StateRate::new("New Jersey".to_string(), 8.75, false, 0.0, "Annual".to_string(), "8.75% per annum".to_string()),
-        StateRate::new("New Mexico".to_string(), 15.0, false, 0.0, "Annual".to_string(), "15% per annum".to_string()),
-        StateRate::new("New York".to_string(), 9.0, false, 0.0, "Annual".to_string(), "9% per annum".to_string()),
-        StateRate::new("North Carolina".to_string(), 8.0, false, 0.0, "Annual".to_string(), "8% per annum".to_string()),
-        StateRate::new("North Dakota".to_string(), 12.0, false, 0.0, "Annual".to_string(), "12% per annum".to_string()),
-        StateRate::new("Ohio".to_string(), 3.0, true, 0.0, "Annual".to_string(), "Federal rate + 3%".to_string()),
-        StateRate::new("Oklahoma".to_string(), 4.5, true, 4.0, "Annual".to_string(), "Prime rate + 4%".to_string()),
-        StateRate::new("Oregon".to_string(), 9.0, false, 0.0, "Annual".to_string(), "9% per annum".to_string()),
-        StateRate::new("Pennsylvania".to_string(), 6.0, false, 0.0, "Annual".to_string(), "6% per annum".to_string()),
-        StateRate::new("Rhode Island".to_string(), 12.0, false, 0.0, "Annual".to_string(), "12% per annum".to_string()),
-        StateRate::new("South Carolina".to_string(), 8.75, false, 0.0, "Annual".to_string(), "8.75% per annum".to_string()),
-        StateRate::new("South Dakota".to_string(), 10.0, false, 0.0, "Annual".to_string(), "10% per annum".to_string()),
-        StateRate::new("Tennessee".to_string(), 10.0, false, 0.0, "Annual".to_string(), "10% per annum".to_string()),
-        StateRate::new("Texas".to_string(), 5.0, false, 0.0, "Annual".to_string(), "5% per annum if parties agree, 18% otherwise".to_string()),
-        StateRate::new("Utah".to_string(), 2.0, true, 0.0, "Annual".to_string(), "Federal rate + 2%".to_string()),
-        StateRate::new("Vermont".to_string(), 12.0, false, 0.0, "Annual".to_string(), "12% per annum".to_string()),
-        StateRate::new("Virginia".to_string(), 6.0, false, 0.0, "Annual".to_string(), "6% per annum".to_string()),
-        StateRate::new("Washington".to_string(), 12.0, false, 0.0, "Annual".to_string(), "12% per annum or contract rate".to_string()),
-        StateRate::new("West Virginia".to_string(), 7.0, false, 0.0, "Annual".to_string(), "7% per annum".to_string()),
-        StateRate::new("Wisconsin".to_string(), 5.0, false, 0.0, "Annual".to_string(), "5% per annum".to_string()),
-        StateRate::new("Wyoming".to_string(), 10.0, false, 0.0, "Annual".to_string(), "10% per annum".to_string()),
-    ];
-
-    for state in initial_states {
-        insert_state_rate(conn, &state)?;
+    // Rates were stored as REAL before they became exact decimals
+    convert_real_columns(conn, "state_rate_periods", STATE_RATE_PERIODS_COLUMNS, &["rate"])?;
+
+    conn.execute(
+        &format!("CREATE TABLE IF NOT EXISTS fred_observations {}", FRED_OBSERVATIONS_COLUMNS),
+        [],
+    )?;
+    conn.execute(
+        &format!("CREATE TABLE IF NOT EXISTS fred_coverage {}", FRED_COVERAGE_COLUMNS),
+        [],
+    )?;
+    Ok(())
+}
+
+// Version 2: the rate table's audit trail
+fn migrate_audit(conn: &Connection) -> Result<()> {
+    conn.execute(&format!("CREATE TABLE state_rate_audit {}", STATE_RATE_AUDIT_COLUMNS), [])?;
+    conn.execute(
+        "CREATE INDEX state_rate_audit_state_rate_id ON state_rate_audit (state_rate_id)",
+        [],
+    )?;
+    Ok(())
+}
+
+// Version 3: deleted rows move to a trash instead of being dropped
+fn migrate_soft_delete(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE state_rates ADD COLUMN deleted_at TEXT", [])?;
+    Ok(())
+}
+
+// Version 4: seed tracking. Existing rows get no baseline; the next sync adopts
+// those that match the shipped values.
+fn migrate_seed_baselines(conn: &Connection) -> Result<()> {
+    conn.execute(&format!("CREATE TABLE seed_baselines {}", SEED_BASELINES_COLUMNS), [])?;
+    Ok(())
+}
+
+// Version 5: each row's statutory source
+fn migrate_rate_sources(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE state_rates ADD COLUMN citation TEXT NOT NULL DEFAULT '';
+         ALTER TABLE state_rates ADD COLUMN source_url TEXT NOT NULL DEFAULT '';
+         ALTER TABLE state_rates ADD COLUMN effective_date TEXT;
+         ALTER TABLE state_rates ADD COLUMN verification_note TEXT NOT NULL DEFAULT '';",
+    )
+}
+
+// Version 6: verification stamps, and update frequencies as typed values.
+// Frequencies were free text; unrecognised ones become annual.
+fn migrate_verification(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE state_rates ADD COLUMN verified_by TEXT NOT NULL DEFAULT '';
+         ALTER TABLE state_rates ADD COLUMN verified_on TEXT;
+         ALTER TABLE state_rates ADD COLUMN next_review TEXT;
+         UPDATE state_rates SET update_frequency =
+             CASE replace(replace(lower(trim(update_frequency)), '-', '_'), ' ', '_')
+                 WHEN 'weekly' THEN 'weekly'
+                 WHEN 'monthly' THEN 'monthly'
+                 WHEN 'quarterly' THEN 'quarterly'
+                 WHEN 'semi_annual' THEN 'semi_annual'
+                 WHEN 'semiannual' THEN 'semi_annual'
+                 ELSE 'annual'
+             END;",
+    )
+}
+
+// Version 7: saved matters and their calculations
+fn migrate_matters(conn: &Connection) -> Result<()> {
+    conn.execute_batch(&format!(
+        "CREATE TABLE matters {};
+         CREATE TABLE calculations {};
+         CREATE INDEX calculations_matter ON calculations (matter_id);",
+        MATTERS_COLUMNS, CALCULATIONS_COLUMNS
+    ))
+}
+
+// Version 8: audit entries for rate period edits hold the row's periods as JSON
+fn migrate_period_audit(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE state_rate_audit ADD COLUMN before_periods TEXT;
+         ALTER TABLE state_rate_audit ADD COLUMN after_periods TEXT;",
+    )
+}
+
+// Returns true when the column was missing and has been added
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<bool> {
+    if table_columns(conn, table)?.iter().any(|(name, _)| name == column) {
+        return Ok(false);
+    }
+
+    conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    Ok(true)
+}
+
+// SQLite cannot change a column's type in place, so a table whose decimal
+// columns are still declared REAL is copied into a new table with the current
+// definition, converting those values to text
+fn convert_real_columns(
+    conn: &Connection,
+    table: &str,
+    definition: &str,
+    decimal_columns: &[&str],
+) -> Result<()> {
+    let columns = table_columns(conn, table)?;
+    let needs_conversion = columns
+        .iter()
+        .any(|(name, kind)| {
+            decimal_columns.contains(&name.as_str()) && kind.eq_ignore_ascii_case("REAL")
+        });
+    if !needs_conversion {
+        return Ok(());
     }
 
+    let names: Vec<&str> = columns.iter().map(|(name, _)| name.as_str()).collect();
+    let values: Vec<String> = names
+        .iter()
+        .map(|name| {
+            if decimal_columns.contains(name) {
+                format!("CAST({} AS TEXT)", name)
+            } else {
+                name.to_string()
+            }
+        })
+        .collect();
+
+    conn.execute(&format!("CREATE TABLE {}_new {}", table, definition), [])?;
+    conn.execute(
+        &format!(
+            "INSERT INTO {0}_new ({1}) SELECT {2} FROM {0}",
+            table,
+            names.join(", "),
+            values.join(", ")
+        ),
+        [],
+    )?;
+    conn.execute(&format!("DROP TABLE {}", table), [])?;
+    conn.execute(&format!("ALTER TABLE {0}_new RENAME TO {0}", table), [])?;
     Ok(())
 }
 
+// Replaces the legacy rate columns with a formula: the index plus spread for
+// variable rates, the fixed rate otherwise. A variable rate that names no
+// index keeps its last fixed rate and says so in its notes rather than
+// silently floating on the wrong index.
+fn convert_rate_formulas(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "state_rates", "rate_formula", "TEXT")?;
+
+    let legacy: Vec<(i32, RateFormula, bool)> = {
+        let mut stmt = conn.prepare(
+            "SELECT id, rate, is_variable, plus_percentage, rate_index FROM state_rates",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let id = row.get(0)?;
+            let rate = get_decimal(row, 1)?;
+            let plus_percentage = get_decimal(row, 3)?;
+            let index: Option<RateIndex> = row.get(4)?;
+            Ok(match (row.get::<_, bool>(2)?, index) {
+                (true, Some(index)) => (id, RateFormula::index(index, plus_percentage), false),
+                (true, None) => (id, RateFormula::fixed(rate), true),
+                (false, _) => (id, RateFormula::fixed(rate), false),
+            })
+        })?;
+        rows.collect::<Result<_>>()?
+    };
+
+    for (id, formula, missing_index) in legacy {
+        conn.execute(
+            "UPDATE state_rates SET rate_formula = ?1 WHERE id = ?2",
+            params![formula, id],
+        )?;
+        if missing_index {
+            conn.execute(
+                "UPDATE state_rates SET notes = COALESCE(notes, '') || ' ' || ?1 WHERE id = ?2",
+                params![MISSING_INDEX_NOTE, id],
+            )?;
+        }
+    }
+
+    // Copy the remaining columns into a table without the legacy ones
+    let legacy_columns = ["rate", "is_variable", "plus_percentage", "rate_index"];
+    let current: Vec<String> = table_columns(conn, "state_rates")?
+        .into_iter()
+        .map(|(name, _)| name)
+        .filter(|name| !legacy_columns.contains(&name.as_str()))
+        .collect();
+    conn.execute(&format!("CREATE TABLE state_rates_new {}", STATE_RATES_COLUMNS), [])?;
+    conn.execute(
+        &format!(
+            "INSERT INTO state_rates_new ({0}) SELECT {0} FROM state_rates",
+            current.join(", ")
+        ),
+        [],
+    )?;
+    conn.execute("DROP TABLE state_rates", [])?;
+    conn.execute("ALTER TABLE state_rates_new RENAME TO state_rates", [])?;
+    Ok(())
+}
+
+// (name, declared type) of each column of the table
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt.query_map([], |row| Ok((row.get(1)?, row.get(2)?)))?;
+    columns.collect()
+}
+
+// SQLite enforces foreign keys only on connections that turn them on
 pub fn get_connection(app: &AppHandle) -> Result<Connection> {
     let db_path = get_db_path(app);
-    Connection::open(db_path)
+    let conn = Connection::open(db_path)?;
+    conn.execute_batch("PRAGMA foreign_keys = ON")?;
+    Ok(conn)
 }
 
+const STATE_RATE_SELECT: &str =
+    "SELECT id, state, rate_formula, update_frequency, last_update, notes, compounding, day_count,
+            rounding_stage, rounding_mode, rate_date_rule, deleted_at, citation, source_url,
+            effective_date, verification_note, verified_by, verified_on, next_review
+     FROM state_rates";
+
+// Trashed rows are invisible to lookups and calculations
 pub fn get_state_rate(app: &AppHandle, state: &str) -> Result<Option<StateRate>> {
     let conn = get_connection(app)?;
-    let mut stmt = conn.prepare(
-        "SELECT id, state, rate, is_variable, plus_percentage, update_frequency, last_update, notes
-         FROM state_rates WHERE state = ?1"
-    )?;
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE state = ?1 AND deleted_at IS NULL",
+        STATE_RATE_SELECT
+    ))?;
     
-    let state_rate = stmt.query_row(params![state], |row| {
-        Ok(StateRate {
-            id: row.get(0)?,
-            state: row.get(1)?,
-            rate: row.get(2)?,
-            is_variable: row.get(3)?,
-            plus_percentage: row.get(4)?,
-            update_frequency: row.get(5)?,
-            last_update: row.get(6)?,
-            notes: row.get(7)?,
-        })
-    }).optional()?;
+    let state_rate = stmt.query_row(params![state], map_state_rate).optional()?;
 
     Ok(state_rate)
 }
 
-pub fn get_all_states(app: &AppHandle) -> Result<Vec<StateRate>> {
-    let conn = get_connection(app)?;
-    let mut stmt = conn.prepare(
-        "SELECT id, state, rate, is_variable, plus_percentage, update_frequency, last_update, notes
-         FROM state_rates ORDER BY state"
-    )?;
+pub fn get_all_states(conn: &Connection) -> Result<Vec<StateRate>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE deleted_at IS NULL ORDER BY state",
+        STATE_RATE_SELECT
+    ))?;
     
-    let state_rates = stmt.query_map([], |row| {
-        Ok(StateRate {
-            id: row.get(0)?,
-            state: row.get(1)?,
-            rate: row.get(2)?,
-            is_variable: row.get(3)?,
-            plus_percentage: row.get(4)?,
-            update_frequency: row.get(5)?,
-            last_update: row.get(6)?,
-            notes: row.get(7)?,
-        })
-    })?;
+    let state_rates = stmt.query_map([], map_state_rate)?;
+
+    state_rates.collect()
+}
 
+// Rows never verified, then those whose review date is before `today`, most
+// overdue first
+pub fn get_overdue_states(conn: &Connection, today: &str) -> Result<Vec<StateRate>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE deleted_at IS NULL AND (next_review IS NULL OR next_review < ?1)
+         ORDER BY next_review IS NOT NULL, next_review, state",
+        STATE_RATE_SELECT
+    ))?;
+    let state_rates = stmt.query_map(params![today], map_state_rate)?;
     state_rates.collect()
 }
 
-pub fn update_state_rate(app: &AppHandle, state_rate: &StateRate) -> Result<()> {
+// Most recently deleted first
+pub fn get_trashed_states(app: &AppHandle) -> Result<Vec<StateRate>> {
     let conn = get_connection(app)?;
-    conn.execute(
-        "UPDATE state_rates SET rate = ?1, is_variable = ?2, plus_percentage = ?3,
-         update_frequency = ?4, last_update = ?5, notes = ?6 WHERE id = ?7",
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        STATE_RATE_SELECT
+    ))?;
+    let state_rates = stmt.query_map([], map_state_rate)?;
+    state_rates.collect()
+}
+
+// Includes trashed rows
+pub fn get_state_rate_by_id(conn: &Connection, id: i32) -> Result<Option<StateRate>> {
+    conn.query_row(
+        &format!("{} WHERE id = ?1", STATE_RATE_SELECT),
+        params![id],
+        map_state_rate,
+    )
+    .optional()
+}
+
+// Case-insensitive; includes trashed rows
+pub fn get_state_rate_by_name(conn: &Connection, state: &str) -> Result<Option<StateRate>> {
+    conn.query_row(
+        &format!("{} WHERE state = ?1 COLLATE NOCASE", STATE_RATE_SELECT),
+        params![state],
+        map_state_rate,
+    )
+    .optional()
+}
+
+// Decimals are stored as text; values written by older versions may be REAL
+fn get_decimal(row: &Row, idx: usize) -> Result<Decimal> {
+    let value = row.get_ref(idx)?;
+    let decimal = match value {
+        ValueRef::Text(text) => std::str::from_utf8(text)
+            .ok()
+            .and_then(|text| Decimal::from_str(text).ok()),
+        ValueRef::Real(real) => Decimal::from_str(&real.to_string()).ok(),
+        ValueRef::Integer(integer) => Some(Decimal::from(integer)),
+        _ => None,
+    };
+
+    decimal.ok_or_else(|| {
+        rusqlite::Error::FromSqlConversionFailure(idx, value.data_type(), "invalid decimal".into())
+    })
+}
+
+fn map_state_rate(row: &Row) -> Result<StateRate> {
+    Ok(StateRate {
+        id: row.get(0)?,
+        state: row.get(1)?,
+        formula: row.get(2)?,
+        update_frequency: row.get(3)?,
+        last_update: row.get(4)?,
+        notes: row.get(5)?,
+        compounding: row.get(6)?,
+        day_count: row.get(7)?,
+        rounding: RoundingPolicy {
+            stage: row.get(8)?,
+            mode: row.get(9)?,
+        },
+        rate_date_rule: row.get(10)?,
+        deleted_at: row.get(11)?,
+        citation: row.get(12)?,
+        source_url: row.get(13)?,
+        effective_date: row.get(14)?,
+        verification_note: row.get(15)?,
+        verified_by: row.get(16)?,
+        verified_on: row.get(17)?,
+        next_review: row.get(18)?,
+    })
+}
+
+// The audited writes below record their audit entry on the same connection;
+// callers run them in a transaction so the change and its entry commit together
+pub fn update_state_rate(
+    conn: &Connection,
+    state_rate: &StateRate,
+    context: &AuditContext,
+) -> Result<()> {
+    let before = get_state_rate_by_id(conn, state_rate.id)?;
+    let updated = conn.execute(
+        "UPDATE state_rates SET state = ?1, rate_formula = ?2, update_frequency = ?3,
+         last_update = ?4, notes = ?5, compounding = ?6, day_count = ?7, rounding_stage = ?8,
+         rounding_mode = ?9, rate_date_rule = ?10, citation = ?11, source_url = ?12,
+         effective_date = ?13, verification_note = ?14, verified_by = ?15, verified_on = ?16,
+         next_review = ?17 WHERE id = ?18",
         params![
-            state_rate.rate,
-            state_rate.is_variable,
-            state_rate.plus_percentage,
+            state_rate.state,
+            state_rate.formula,
             state_rate.update_frequency,
             state_rate.last_update,
             state_rate.notes,
+            state_rate.compounding,
+            state_rate.day_count,
+            state_rate.rounding.stage,
+            state_rate.rounding.mode,
+            state_rate.rate_date_rule,
+            state_rate.citation,
+            state_rate.source_url,
+            state_rate.effective_date,
+            state_rate.verification_note,
+            state_rate.verified_by,
+            state_rate.verified_on,
+            state_rate.review_due(),
             state_rate.id
         ],
     )?;
+    // A row that is gone has nothing to update, so say so rather than succeed
+    if updated == 0 {
+        return Err(rusqlite::Error::QueryReturnedNoRows);
+    }
+    if let Some(before) = before {
+        let after = get_state_rate_by_id(conn, state_rate.id)?;
+        record_audit(conn, AuditAction::Update, Some(&before), after.as_ref(), context)?;
+    }
     Ok(())
 }
 
-pub fn insert_state_rate(conn: &Connection, state_rate: &StateRate) -> Result<()> {
+// A row restored from the audit trail keeps its id
+pub fn create_state_rate(
+    conn: &Connection,
+    state_rate: &StateRate,
+    context: &AuditContext,
+) -> Result<StateRate> {
+    let id = insert_state_rate(conn, state_rate)? as i32;
+    let created = StateRate { id, next_review: state_rate.review_due(), ..state_rate.clone() };
+    record_audit(conn, AuditAction::Insert, None, Some(&created), context)?;
+    Ok(created)
+}
+
+pub fn insert_state_rate(conn: &Connection, state_rate: &StateRate) -> Result<i64> {
     conn.execute(
-        "INSERT INTO state_rates (state, rate, is_variable, plus_percentage, update_frequency, last_update, notes)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT INTO state_rates (id, state, rate_formula, update_frequency, last_update, notes,
+                                  compounding, day_count, rounding_stage, rounding_mode, rate_date_rule,
+                                  citation, source_url, effective_date, verification_note,
+                                  verified_by, verified_on, next_review)
+         VALUES (NULLIF(?11, 0), ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?12, ?13, ?14, ?15, ?16,
+                 ?17, ?18)",
         params![
             state_rate.state,
-            state_rate.rate,
-            state_rate.is_variable,
-            state_rate.plus_percentage,
+            state_rate.formula,
             state_rate.update_frequency,
             state_rate.last_update,
-            state_rate.notes
+            state_rate.notes,
+            state_rate.compounding,
+            state_rate.day_count,
+            state_rate.rounding.stage,
+            state_rate.rounding.mode,
+            state_rate.rate_date_rule,
+            state_rate.id,
+            state_rate.citation,
+            state_rate.source_url,
+            state_rate.effective_date,
+            state_rate.verification_note,
+            state_rate.verified_by,
+            state_rate.verified_on,
+            state_rate.review_due()
         ],
     )?;
+    Ok(conn.last_insert_rowid())
+}
+
+// Whether another jurisdiction already has the name, ignoring case
+pub fn state_name_taken(conn: &Connection, state: &str, except_id: Option<i32>) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM state_rates WHERE state = ?1 COLLATE NOCASE AND id IS NOT ?2)",
+        params![state, except_id],
+        |row| row.get(0),
+    )
+}
+
+// Moves the row to the trash; its rate periods stay with it for a restore
+pub fn delete_state_rate(conn: &Connection, id: i32, context: &AuditContext) -> Result<()> {
+    let before = get_state_rate_by_id(conn, id)?;
+    let trashed = conn.execute(
+        "UPDATE state_rates SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+        params![chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(), id],
+    )?;
+    if let Some(before) = before.filter(|_| trashed > 0) {
+        record_audit(conn, AuditAction::Delete, Some(&before), None, context)?;
+    }
     Ok(())
 }
 
-pub fn delete_state_rate(app: &AppHandle, id: i32) -> Result<()> {
-    let conn = get_connection(app)?;
-    conn.execute("DELETE FROM state_rates WHERE id = ?1", params![id])?;
+pub fn restore_state_rate(conn: &Connection, id: i32, context: &AuditContext) -> Result<()> {
+    let before = get_state_rate_by_id(conn, id)?;
+    let restored = conn.execute(
+        "UPDATE state_rates SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+        params![id],
+    )?;
+    if restored > 0 {
+        let after = get_state_rate_by_id(conn, id)?;
+        record_audit(conn, AuditAction::Restore, before.as_ref(), after.as_ref(), context)?;
+    }
+    Ok(())
+}
+
+// Puts a row back as an audit snapshot recorded it, out of the trash, recreating
+// it under its old id if it has since been purged. Entries for rate period edits
+// restore the row's periods as well.
+pub fn revert_state_rate(
+    conn: &Connection,
+    target: &StateRate,
+    periods: Option<&[StateRatePeriod]>,
+    context: &AuditContext,
+) -> Result<StateRate> {
+    let target = StateRate { deleted_at: None, ..target.clone() };
+    let reverted = match get_state_rate_by_id(conn, target.id)? {
+        Some(existing) => {
+            if existing.deleted_at.is_some() {
+                restore_state_rate(conn, target.id, context)?;
+            }
+            update_state_rate(conn, &target, context)?;
+            target
+        }
+        None => create_state_rate(conn, &target, context)?,
+    };
+    if let Some(periods) = periods {
+        replace_rate_periods(conn, reverted.id, periods, context)?;
+    }
+    Ok(reverted)
+}
+
+// Permanently removes a trashed row and its rate periods; its audit trail remains
+pub fn purge_state_rate(conn: &Connection, id: i32, context: &AuditContext) -> Result<()> {
+    let before = get_state_rate_by_id(conn, id)?;
+    conn.execute(
+        "DELETE FROM state_rate_periods
+         WHERE state_id = (SELECT id FROM state_rates WHERE id = ?1 AND deleted_at IS NOT NULL)",
+        params![id],
+    )?;
+    let purged = conn.execute(
+        "DELETE FROM state_rates WHERE id = ?1 AND deleted_at IS NOT NULL",
+        params![id],
+    )?;
+    if purged > 0 {
+        unlink_seed_baselines(conn)?;
+    }
+    if let Some(before) = before.filter(|_| purged > 0) {
+        record_audit(conn, AuditAction::Purge, Some(&before), None, context)?;
+    }
+    Ok(())
+}
+
+// Clears baseline links to purged rows. Done by hand as well as by ON DELETE SET
+// NULL, since connections opened before foreign keys were enforced left them.
+fn unlink_seed_baselines(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "UPDATE seed_baselines SET state_rate_id = NULL
+         WHERE state_rate_id NOT IN (SELECT id FROM state_rates)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn record_audit(
+    conn: &Connection,
+    action: AuditAction,
+    before: Option<&StateRate>,
+    after: Option<&StateRate>,
+    context: &AuditContext,
+) -> Result<()> {
+    insert_audit_entry(conn, action, before, after, None, context)
+}
+
+// Records a change to a row's rate periods as an update of the row, with its
+// whole rate history before and after; nothing is recorded if they are unchanged
+fn record_period_audit(
+    conn: &Connection,
+    state_id: i32,
+    before: &[StateRatePeriod],
+    context: &AuditContext,
+) -> Result<()> {
+    let Some(row) = get_state_rate_by_id(conn, state_id)? else { return Ok(()) };
+    let after = get_rate_periods(conn, state_id)?;
+    if after == before {
+        return Ok(());
+    }
+    let periods = Some((before, after.as_slice()));
+    insert_audit_entry(conn, AuditAction::Update, Some(&row), Some(&row), periods, context)
+}
+
+fn insert_audit_entry(
+    conn: &Connection,
+    action: AuditAction,
+    before: Option<&StateRate>,
+    after: Option<&StateRate>,
+    periods: Option<(&[StateRatePeriod], &[StateRatePeriod])>,
+    context: &AuditContext,
+) -> Result<()> {
+    let row = after.or(before).expect("an audit entry snapshots at least one side");
+    let json = |periods: &[StateRatePeriod]| {
+        serde_json::to_string(periods)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+    };
+    let (before_periods, after_periods) = match periods {
+        Some((before, after)) => (Some(json(before)?), Some(json(after)?)),
+        None => (None, None),
+    };
+    conn.execute(
+        "INSERT INTO state_rate_audit
+            (state_rate_id, state, action, before, after, changed_at, changed_by, reason,
+             before_periods, after_periods)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            row.id,
+            row.state,
+            action,
+            before,
+            after,
+            timestamp(),
+            context.changed_by,
+            context.reason,
+            before_periods,
+            after_periods
+        ],
+    )?;
+    Ok(())
+}
+
+const STATE_RATE_AUDIT_SELECT: &str =
+    "SELECT id, state_rate_id, state, action, before, after, changed_at, changed_by, reason,
+            before_periods, after_periods
+     FROM state_rate_audit";
+
+// Newest first
+pub fn get_state_rate_audit(conn: &Connection, state_rate_id: i32) -> Result<Vec<StateRateAudit>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE state_rate_id = ?1 ORDER BY id DESC",
+        STATE_RATE_AUDIT_SELECT
+    ))?;
+    let entries = stmt.query_map(params![state_rate_id], map_state_rate_audit)?;
+    entries.collect()
+}
+
+pub fn get_audit_entry(conn: &Connection, id: i64) -> Result<Option<StateRateAudit>> {
+    conn.query_row(
+        &format!("{} WHERE id = ?1", STATE_RATE_AUDIT_SELECT),
+        params![id],
+        map_state_rate_audit,
+    )
+    .optional()
+}
+
+fn map_state_rate_audit(row: &Row) -> Result<StateRateAudit> {
+    Ok(StateRateAudit {
+        id: row.get(0)?,
+        state_rate_id: row.get(1)?,
+        state: row.get(2)?,
+        action: row.get(3)?,
+        before: row.get(4)?,
+        after: row.get(5)?,
+        changed_at: row.get(6)?,
+        changed_by: row.get(7)?,
+        reason: row.get(8)?,
+        before_periods: get_periods_json(row, 9)?,
+        after_periods: get_periods_json(row, 10)?,
+    })
+}
+
+fn get_periods_json(row: &Row, index: usize) -> Result<Option<Vec<StateRatePeriod>>> {
+    let Some(json) = row.get::<_, Option<String>>(index)? else { return Ok(None) };
+    serde_json::from_str(&json).map(Some).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+// Whether any row by this name, ignoring case, has ever been recorded
+pub fn state_in_audit(conn: &Connection, state: &str) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM state_rate_audit WHERE state = ?1 COLLATE NOCASE)",
+        params![state],
+        |row| row.get(0),
+    )
+}
+
+pub fn get_seed_baseline(conn: &Connection, state: &str) -> Result<Option<SeedBaseline>> {
+    conn.query_row(
+        "SELECT state_rate_id, shipped FROM seed_baselines WHERE state = ?1",
+        params![state],
+        |row| Ok(SeedBaseline { state_rate_id: row.get(0)?, shipped: row.get(1)? }),
+    )
+    .optional()
+}
+
+pub fn record_seed_baseline(
+    conn: &Connection,
+    state_rate_id: Option<i32>,
+    seed_version: i32,
+    shipped: &SeedRow,
+) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO seed_baselines (state, state_rate_id, seed_version, shipped)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![shipped.state, state_rate_id, seed_version, shipped],
+    )?;
+    Ok(())
+}
+
+fn timestamp() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+const MATTER_SELECT: &str =
+    "SELECT id, caption, docket_number, court, client, judgment_date, principal, jurisdiction,
+            created_at, updated_at,
+            (SELECT COUNT(*) FROM calculations WHERE matter_id = matters.id)
+     FROM matters";
+
+// Most recently changed first
+pub fn get_matters(conn: &Connection) -> Result<Vec<Matter>> {
+    let mut stmt =
+        conn.prepare(&format!("{} ORDER BY updated_at DESC, id DESC", MATTER_SELECT))?;
+    let matters = stmt.query_map([], map_matter)?;
+    matters.collect()
+}
+
+pub fn get_matter(conn: &Connection, id: i64) -> Result<Option<Matter>> {
+    conn.query_row(&format!("{} WHERE id = ?1", MATTER_SELECT), params![id], map_matter)
+        .optional()
+}
+
+fn map_matter(row: &Row) -> Result<Matter> {
+    Ok(Matter {
+        id: row.get(0)?,
+        caption: row.get(1)?,
+        docket_number: row.get(2)?,
+        court: row.get(3)?,
+        client: row.get(4)?,
+        judgment_date: row.get(5)?,
+        principal: get_decimal(row, 6)?,
+        jurisdiction: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+        calculation_count: row.get(10)?,
+    })
+}
+
+pub fn insert_matter(conn: &Connection, matter: &Matter) -> Result<i64> {
+    let now = timestamp();
+    conn.execute(
+        "INSERT INTO matters (caption, docket_number, court, client, judgment_date, principal,
+                              jurisdiction, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+        params![
+            matter.caption,
+            matter.docket_number,
+            matter.court,
+            matter.client,
+            matter.judgment_date,
+            matter.principal.normalize().to_string(),
+            matter.jurisdiction,
+            now
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_matter(conn: &Connection, matter: &Matter) -> Result<()> {
+    conn.execute(
+        "UPDATE matters SET caption = ?1, docket_number = ?2, court = ?3, client = ?4,
+                            judgment_date = ?5, principal = ?6, jurisdiction = ?7, updated_at = ?8
+         WHERE id = ?9",
+        params![
+            matter.caption,
+            matter.docket_number,
+            matter.court,
+            matter.client,
+            matter.judgment_date,
+            matter.principal.normalize().to_string(),
+            matter.jurisdiction,
+            timestamp(),
+            matter.id
+        ],
+    )?;
+    Ok(())
+}
+
+// Removes the matter with its saved calculations
+pub fn delete_matter(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM calculations WHERE matter_id = ?1", params![id])?;
+    conn.execute("DELETE FROM matters WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+// Saving a calculation counts as a change to its matter
+pub fn insert_calculation(
+    conn: &Connection,
+    matter_id: i64,
+    name: &str,
+    request: &CalcRequest,
+    response: &CalcResponse,
+) -> Result<i64> {
+    let now = timestamp();
+    conn.execute(
+        "INSERT INTO calculations (matter_id, name, request, response, calculated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![matter_id, name, request, response, now],
+    )?;
+    let id = conn.last_insert_rowid();
+    conn.execute("UPDATE matters SET updated_at = ?1 WHERE id = ?2", params![now, matter_id])?;
+    Ok(id)
+}
+
+// Newest first
+pub fn get_calculations(conn: &Connection, matter_id: i64) -> Result<Vec<CalculationSummary>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, matter_id, name, calculated_at, json_extract(request, '$.from_date'),
+                json_extract(request, '$.to_date'), json_extract(response, '$.rate'),
+                json_extract(response, '$.total_amount')
+         FROM calculations WHERE matter_id = ?1 ORDER BY id DESC",
+    )?;
+    let calculations = stmt.query_map(params![matter_id], |row| {
+        Ok(CalculationSummary {
+            id: row.get(0)?,
+            matter_id: row.get(1)?,
+            name: row.get(2)?,
+            calculated_at: row.get(3)?,
+            from_date: row.get(4)?,
+            to_date: row.get(5)?,
+            rate: get_decimal(row, 6)?,
+            total_amount: get_decimal(row, 7)?,
+        })
+    })?;
+    calculations.collect()
+}
+
+pub fn get_calculation(conn: &Connection, id: i64) -> Result<Option<SavedCalculation>> {
+    conn.query_row(
+        "SELECT id, matter_id, name, request, response, calculated_at
+         FROM calculations WHERE id = ?1",
+        params![id],
+        |row| {
+            Ok(SavedCalculation {
+                id: row.get(0)?,
+                matter_id: row.get(1)?,
+                name: row.get(2)?,
+                request: row.get(3)?,
+                response: row.get(4)?,
+                calculated_at: row.get(5)?,
+            })
+        },
+    )
+    .optional()
+}
+
+pub fn rename_calculation(conn: &Connection, id: i64, name: &str) -> Result<()> {
+    conn.execute("UPDATE calculations SET name = ?1 WHERE id = ?2", params![name, id])?;
     Ok(())
 }
+
+pub fn delete_calculation(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM calculations WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+pub fn get_rate_periods(conn: &Connection, state_id: i32) -> Result<Vec<StateRatePeriod>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, state_id, effective_from, effective_to, rate, notes
+         FROM state_rate_periods WHERE state_id = ?1 ORDER BY effective_from"
+    )?;
+
+    let periods = stmt.query_map(params![state_id], |row| {
+        Ok(StateRatePeriod {
+            id: row.get(0)?,
+            state_id: row.get(1)?,
+            effective_from: row.get(2)?,
+            effective_to: row.get(3)?,
+            rate: get_decimal(row, 4)?,
+            notes: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+        })
+    })?;
+
+    periods.collect()
+}
+
+pub fn insert_rate_period(conn: &Connection, period: &StateRatePeriod) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO state_rate_periods (state_id, effective_from, effective_to, rate, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            period.state_id,
+            period.effective_from,
+            period.effective_to,
+            period.rate.normalize().to_string(),
+            period.notes
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+// The audited rate period writes below record an entry against the periods' row.
+// Makes `periods` the state's whole rate history.
+pub fn replace_rate_periods(
+    conn: &Connection,
+    state_id: i32,
+    periods: &[StateRatePeriod],
+    context: &AuditContext,
+) -> Result<()> {
+    let before = get_rate_periods(conn, state_id)?;
+    conn.execute("DELETE FROM state_rate_periods WHERE state_id = ?1", params![state_id])?;
+    for period in periods {
+        insert_rate_period(conn, &StateRatePeriod { state_id, ..period.clone() })?;
+    }
+    record_period_audit(conn, state_id, &before, context)
+}
+
+pub fn create_rate_period(
+    conn: &Connection,
+    period: &StateRatePeriod,
+    context: &AuditContext,
+) -> Result<i64> {
+    let before = get_rate_periods(conn, period.state_id)?;
+    let id = insert_rate_period(conn, period)?;
+    record_period_audit(conn, period.state_id, &before, context)?;
+    Ok(id)
+}
+
+pub fn update_rate_period(
+    conn: &Connection,
+    period: &StateRatePeriod,
+    context: &AuditContext,
+) -> Result<()> {
+    let Some(state_id) = rate_period_state(conn, period.id)? else { return Ok(()) };
+    let before = get_rate_periods(conn, state_id)?;
+    conn.execute(
+        "UPDATE state_rate_periods SET effective_from = ?1, effective_to = ?2, rate = ?3, notes = ?4
+         WHERE id = ?5",
+        params![
+            period.effective_from,
+            period.effective_to,
+            period.rate.normalize().to_string(),
+            period.notes,
+            period.id
+        ],
+    )?;
+    record_period_audit(conn, state_id, &before, context)
+}
+
+pub fn delete_rate_period(conn: &Connection, id: i32, context: &AuditContext) -> Result<()> {
+    let Some(state_id) = rate_period_state(conn, id)? else { return Ok(()) };
+    let before = get_rate_periods(conn, state_id)?;
+    conn.execute("DELETE FROM state_rate_periods WHERE id = ?1", params![id])?;
+    record_period_audit(conn, state_id, &before, context)
+}
+
+fn rate_period_state(conn: &Connection, id: i32) -> Result<Option<i32>> {
+    conn.query_row("SELECT state_id FROM state_rate_periods WHERE id = ?1", params![id], |row| {
+        row.get(0)
+    })
+    .optional()
+}
+
+// Cached observations of a series between the dates, inclusive, in date order
+pub fn get_cached_observations(
+    conn: &Connection,
+    series_id: &str,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<FredObservation>> {
+    let mut stmt = conn.prepare(
+        "SELECT realtime_start, realtime_end, date, value FROM fred_observations
+         WHERE series_id = ?1 AND date BETWEEN ?2 AND ?3
+         ORDER BY date",
+    )?;
+
+    let observations = stmt.query_map(params![series_id, start_date, end_date], |row| {
+        Ok(FredObservation {
+            realtime_start: row.get(0)?,
+            realtime_end: row.get(1)?,
+            date: row.get(2)?,
+            value: row.get(3)?,
+        })
+    })?;
+
+    observations.collect()
+}
+
+// Whether one earlier fetch covered the whole range
+pub fn is_range_cached(
+    conn: &Connection,
+    series_id: &str,
+    start_date: &str,
+    end_date: &str,
+) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM fred_coverage
+         WHERE series_id = ?1 AND start_date <= ?2 AND end_date >= ?3)",
+        params![series_id, start_date, end_date],
+        |row| row.get(0),
+    )
+}
+
+// Stores fetched observations and records the range they cover. Pass
+// `covered_through` as the last date whose observations are final; later
+// dates may still be published and are not marked as covered.
+pub fn cache_observations(
+    conn: &Connection,
+    series_id: &str,
+    start_date: &str,
+    covered_through: &str,
+    observations: &[FredObservation],
+) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    for observation in observations {
+        tx.execute(
+            "INSERT OR REPLACE INTO fred_observations
+                (series_id, date, value, realtime_start, realtime_end)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                series_id,
+                observation.date,
+                observation.value,
+                observation.realtime_start,
+                observation.realtime_end
+            ],
+        )?;
+    }
+    if start_date <= covered_through {
+        tx.execute(
+            "INSERT INTO fred_coverage (series_id, start_date, end_date, fetched_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                series_id,
+                start_date,
+                covered_through,
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+            ],
+        )?;
+    }
+    tx.commit()
+}
+
+// Creates the observation cache tables on a bare connection
+#[cfg(test)]
+pub fn init_fred_cache(conn: &Connection) -> Result<()> {
+    conn.execute(&format!("CREATE TABLE fred_observations {}", FRED_OBSERVATIONS_COLUMNS), [])?;
+    conn.execute(&format!("CREATE TABLE fred_coverage {}", FRED_COVERAGE_COLUMNS), [])?;
+    Ok(())
+}
+
+// Enums are stored as their snake_case names
+macro_rules! text_enum_sql {
+    ($type:ty, $description:literal) => {
+        impl ToSql for $type {
+            fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+                Ok(ToSqlOutput::from(self.as_str()))
+            }
+        }
+
+        impl FromSql for $type {
+            fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                let text = value.as_str()?;
+                <$type>::parse(text).ok_or_else(|| {
+                    FromSqlError::Other(format!("unknown {} '{}'", $description, text).into())
+                })
+            }
+        }
+    };
+}
+
+text_enum_sql!(CompoundingMode, "compounding mode");
+text_enum_sql!(DayCount, "day-count convention");
+text_enum_sql!(RoundingStage, "rounding stage");
+text_enum_sql!(RoundingMode, "rounding mode");
+text_enum_sql!(RateIndex, "rate index");
+text_enum_sql!(RateDateRule, "rate date rule");
+text_enum_sql!(AuditAction, "audit action");
+text_enum_sql!(UpdateFrequency, "update frequency");
+
+// Structured values are stored as JSON
+macro_rules! json_sql {
+    ($type:ty) => {
+        impl ToSql for $type {
+            fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+                let json = serde_json::to_string(self)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+                Ok(ToSqlOutput::from(json))
+            }
+        }
+
+        impl FromSql for $type {
+            fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                serde_json::from_str(value.as_str()?).map_err(|e| FromSqlError::Other(Box::new(e)))
+            }
+        }
+    };
+}
+
+json_sql!(RateFormula);
+json_sql!(StateRate);
+json_sql!(SeedRow);
+json_sql!(CalcRequest);
+json_sql!(CalcResponse);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RateBase;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_convert_real_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE state_rates (id INTEGER PRIMARY KEY AUTOINCREMENT);
+            INSERT INTO state_rates DEFAULT VALUES;
+            CREATE TABLE state_rate_periods (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                state_id INTEGER NOT NULL,
+                effective_from TEXT NOT NULL,
+                effective_to TEXT,
+                rate REAL NOT NULL,
+                notes TEXT
+            );
+            INSERT INTO state_rate_periods (state_id, effective_from, rate, notes)
+            VALUES (1, '2024-01-01', 4.75, '4.75% per annum');",
+        )
+        .unwrap();
+
+        convert_real_columns(&conn, "state_rate_periods", STATE_RATE_PERIODS_COLUMNS, &["rate"])
+            .unwrap();
+
+        let (rate, kind): (String, String) = conn
+            .query_row("SELECT rate, typeof(rate) FROM state_rate_periods", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(rate, "4.75");
+        assert_eq!(kind, "text");
+    }
+
+    #[test]
+    fn test_convert_rate_formulas() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE state_rates (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                state TEXT NOT NULL UNIQUE,
+                rate REAL NOT NULL,
+                is_variable INTEGER NOT NULL,
+                plus_percentage REAL NOT NULL,
+                update_frequency TEXT NOT NULL,
+                last_update TEXT NOT NULL,
+                notes TEXT,
+                compounding TEXT NOT NULL DEFAULT 'none',
+                day_count TEXT NOT NULL DEFAULT 'actual_365',
+                rate_index TEXT
+            );
+            INSERT INTO state_rates
+                (state, rate, is_variable, plus_percentage, update_frequency, last_update, notes,
+                 rate_index)
+            VALUES
+                ('Florida', 4.75, 0, 0.0, 'Annual', '2024-01-01', '4.75% per annum', NULL),
+                ('Oklahoma', 4.5, 1, 4.0, 'Annual', '2024-01-01', 'Prime rate + 4%', 'DPRIME'),
+                ('Nowhere', 6.0, 1, 1.0, 'Annual', '2024-01-01', 'Some index + 1%', NULL);",
+        )
+        .unwrap();
+
+        convert_rate_formulas(&conn).unwrap();
+
+        let columns = table_columns(&conn, "state_rates").unwrap();
+        assert!(!columns.iter().any(|(name, _)| name == "is_variable" || name == "rate"));
+
+        let rows: Vec<StateRate> = conn
+            .prepare(
+                "SELECT id, state, rate_formula, lower(update_frequency), last_update, notes,
+                        compounding, day_count, rounding_stage, rounding_mode, rate_date_rule,
+                        NULL, '', '', NULL, '', '', NULL, NULL
+                 FROM state_rates ORDER BY id",
+            )
+            .unwrap()
+            .query_map([], map_state_rate)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+
+        assert_eq!(rows[0].formula, RateFormula::fixed(dec!(4.75)));
+        assert_eq!(rows[0].rounding, RoundingPolicy::default());
+        assert_eq!(rows[1].formula, RateFormula::index(RateIndex::Prime, dec!(4)));
+        assert_eq!(rows[2].formula.base, RateBase::Fixed { rate: dec!(6) });
+        assert!(rows[2].notes.contains("no index"));
+    }
+
+    #[test]
+    fn test_migrate_legacy_database() {
+        let path = std::env::temp_dir().join(format!("rates-migrate-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE state_rates (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                state TEXT NOT NULL UNIQUE,
+                rate REAL NOT NULL,
+                is_variable INTEGER NOT NULL,
+                plus_percentage REAL NOT NULL,
+                update_frequency TEXT NOT NULL,
+                last_update TEXT NOT NULL,
+                notes TEXT
+            );
+            INSERT INTO state_rates
+                (state, rate, is_variable, plus_percentage, update_frequency, last_update, notes)
+            VALUES ('Colorado', 8.0, 0, 0.0, 'Semi-Annual', '2024-01-01', '8% compounded annually');
+            CREATE TABLE state_rate_periods (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                state_id INTEGER NOT NULL REFERENCES state_rates(id),
+                effective_from TEXT NOT NULL,
+                effective_to TEXT,
+                rate REAL NOT NULL,
+                notes TEXT
+            );
+            INSERT INTO state_rate_periods (state_id, effective_from, rate)
+            VALUES (1, '2024-01-01', 8.0);",
+        )
+        .unwrap();
+
+        migrate(&conn, Some(&path)).unwrap();
+
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+        let compounding: CompoundingMode = conn
+            .query_row("SELECT compounding FROM state_rates", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(compounding, CompoundingMode::Annual);
+        let frequency: UpdateFrequency = conn
+            .query_row("SELECT update_frequency FROM state_rates", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(frequency, UpdateFrequency::SemiAnnual);
+
+        // The pre-upgrade copy still has the legacy schema
+        let dir = std::fs::read_dir(std::env::temp_dir()).unwrap();
+        let prefix = format!("rates-migrate-{}.db.v0-", std::process::id());
+        let backups: Vec<PathBuf> = dir
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|p| p.file_name().is_some_and(|n| n.to_string_lossy().starts_with(&prefix)))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        let backup = Connection::open(&backups[0]).unwrap();
+        assert!(table_columns(&backup, "state_rates")
+            .unwrap()
+            .iter()
+            .any(|(name, _)| name == "is_variable"));
+
+        // Already current: nothing to do and no further backup
+        migrate(&conn, Some(&path)).unwrap();
+
+        drop(backup);
+        drop(conn);
+        let _ = std::fs::remove_file(&backups[0]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_refuses_newer_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1).unwrap();
+
+        let error = migrate(&conn, None).unwrap_err();
+        assert_eq!(error.code, ErrorCode::SchemaTooNew);
+    }
+
+    #[test]
+    fn test_purged_seed_row_does_not_block_upgrade() {
+        // As on a connection that never turned foreign keys on
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, None).unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = OFF").unwrap();
+        let seed = seed::shipped();
+        seed::sync(&conn, &seed).unwrap();
+
+        let context = AuditContext { changed_by: "test".to_string(), reason: None };
+        let alabama = get_state_rate_by_name(&conn, "Alabama").unwrap().unwrap();
+        delete_state_rate(&conn, alabama.id, &context).unwrap();
+        purge_state_rate(&conn, alabama.id, &context).unwrap();
+        assert_eq!(get_seed_baseline(&conn, "Alabama").unwrap().unwrap().state_rate_id, None);
+
+        // As left by a purge from before baselines were unlinked, one version back
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = OFF").unwrap();
+        for step in &MIGRATIONS[..MIGRATIONS.len() - 1] {
+            step(&conn).unwrap();
+        }
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION - 1).unwrap();
+        let shipped = seed.state_rates.iter().find(|row| row.state == "Alabama").unwrap();
+        let id = insert_state_rate(&conn, &shipped.to_state_rate(0)).unwrap() as i32;
+        record_seed_baseline(&conn, Some(id), seed.version, shipped).unwrap();
+        conn.execute("DELETE FROM state_rates WHERE id = ?1", params![id]).unwrap();
+
+        migrate(&conn, None).unwrap();
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_state_name_taken() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, None).unwrap();
+        let id = insert_state_rate(
+            &conn,
+            &StateRate::new(
+                "Guam".to_string(),
+                RateFormula::fixed(dec!(6)),
+                UpdateFrequency::Annual,
+                String::new(),
+            ),
+        )
+        .unwrap() as i32;
+
+        assert!(state_name_taken(&conn, "GUAM", None).unwrap());
+        assert!(!state_name_taken(&conn, "guam", Some(id)).unwrap());
+        assert!(!state_name_taken(&conn, "Puerto Rico", None).unwrap());
+
+        let mut renamed = get_state_rate_by_id(&conn, id).unwrap().unwrap();
+        renamed.state = "GUAM".to_string();
+        let context = AuditContext { changed_by: "test".to_string(), reason: None };
+        update_state_rate(&conn, &renamed, &context).unwrap();
+        assert_eq!(get_state_rate_by_id(&conn, id).unwrap().unwrap().state, "GUAM");
+    }
+
+    #[test]
+    fn test_overdue_states() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, None).unwrap();
+        let row = |state: &str, frequency, verified_on: Option<&str>| StateRate {
+            verified_on: verified_on.map(str::to_string),
+            ..StateRate::new(state.to_string(), RateFormula::fixed(dec!(6)), frequency, String::new())
+        };
+        insert_state_rate(&conn, &row("Guam", UpdateFrequency::Annual, None)).unwrap();
+        insert_state_rate(&conn, &row("Palau", UpdateFrequency::Annual, Some("2024-03-01")))
+            .unwrap();
+        insert_state_rate(&conn, &row("Yap", UpdateFrequency::Weekly, Some("2024-03-01")))
+            .unwrap();
+
+        let overdue = get_overdue_states(&conn, "2024-06-01").unwrap();
+        let states: Vec<&str> = overdue.iter().map(|row| row.state.as_str()).collect();
+        assert_eq!(states, vec!["Guam", "Yap"]);
+        assert_eq!(overdue[1].next_review.as_deref(), Some("2024-03-08"));
+
+        let overdue = get_overdue_states(&conn, "2025-03-02").unwrap();
+        assert_eq!(overdue.len(), 3);
+        assert_eq!(overdue[2].next_review.as_deref(), Some("2025-03-01"));
+    }
+
+    #[test]
+    fn test_audit_snapshots_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, None).unwrap();
+        let before = StateRate::new(
+            "New Jersey".to_string(),
+            RateFormula::fixed(dec!(8.75)),
+            UpdateFrequency::Annual,
+            String::new(),
+        );
+        let before = StateRate { id: insert_state_rate(&conn, &before).unwrap() as i32, ..before };
+        let after = StateRate { formula: RateFormula::fixed(dec!(87.5)), ..before.clone() };
+        let context = AuditContext {
+            changed_by: "clerk".to_string(),
+            reason: Some("Annual update".to_string()),
+        };
+
+        record_audit(&conn, AuditAction::Update, Some(&before), Some(&after), &context).unwrap();
+        record_audit(&conn, AuditAction::Delete, Some(&after), None, &context).unwrap();
+
+        let history = get_state_rate_audit(&conn, before.id).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].action, AuditAction::Delete);
+        assert!(history[0].after.is_none());
+        assert_eq!(history[1].state, "New Jersey");
+        assert_eq!(history[1].before.as_ref().unwrap().formula, RateFormula::fixed(dec!(8.75)));
+        assert_eq!(history[1].changed_by, "clerk");
+
+        let entry = get_audit_entry(&conn, history[1].id).unwrap().unwrap();
+        assert_eq!(entry.after.unwrap().formula, RateFormula::fixed(dec!(87.5)));
+    }
+
+    #[test]
+    fn test_revert_purged_row() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, None).unwrap();
+        let context = AuditContext { changed_by: "clerk".to_string(), reason: None };
+        let guam = StateRate::new(
+            "Guam".to_string(),
+            RateFormula::fixed(dec!(6)),
+            UpdateFrequency::Annual,
+            String::new(),
+        );
+        let guam = create_state_rate(&conn, &guam, &context).unwrap();
+        delete_state_rate(&conn, guam.id, &context).unwrap();
+        purge_state_rate(&conn, guam.id, &context).unwrap();
+
+        let purge = &get_state_rate_audit(&conn, guam.id).unwrap()[0];
+        assert_eq!(purge.action, AuditAction::Purge);
+        let trashed = purge.before.as_ref().unwrap();
+        assert!(trashed.deleted_at.is_some());
+
+        let reverted = revert_state_rate(&conn, trashed, None, &context).unwrap();
+        assert_eq!(reverted.id, guam.id);
+        assert_eq!(reverted.deleted_at, None);
+        assert_eq!(get_state_rate_by_id(&conn, guam.id).unwrap().unwrap().deleted_at, None);
+        let insert = &get_state_rate_audit(&conn, guam.id).unwrap()[0];
+        assert_eq!(insert.action, AuditAction::Insert);
+        assert_eq!(insert.after.as_ref().unwrap().deleted_at, None);
+    }
+
+    #[test]
+    fn test_update_missing_row() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, None).unwrap();
+        let context = AuditContext { changed_by: "clerk".to_string(), reason: None };
+        let missing = StateRate {
+            id: 9999,
+            ..StateRate::new(
+                "Guam".to_string(),
+                RateFormula::fixed(dec!(6)),
+                UpdateFrequency::Annual,
+                String::new(),
+            )
+        };
+        let error = update_state_rate(&conn, &missing, &context).unwrap_err();
+        assert!(matches!(error, rusqlite::Error::QueryReturnedNoRows));
+        assert!(get_state_rate_audit(&conn, 9999).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rate_period_edits_are_audited() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, None).unwrap();
+        let context = AuditContext { changed_by: "clerk".to_string(), reason: None };
+        let guam = StateRate::new(
+            "Guam".to_string(),
+            RateFormula::fixed(dec!(6)),
+            UpdateFrequency::Annual,
+            String::new(),
+        );
+        let guam = create_state_rate(&conn, &guam, &context).unwrap();
+        let period = StateRatePeriod {
+            id: 0,
+            state_id: guam.id,
+            effective_from: "2020-01-01".to_string(),
+            effective_to: None,
+            rate: dec!(6),
+            notes: String::new(),
+        };
+        let id = create_rate_period(&conn, &period, &context).unwrap() as i32;
+        let changed = StateRatePeriod { id, rate: dec!(7), ..period };
+        update_rate_period(&conn, &changed, &context).unwrap();
+        delete_rate_period(&conn, id, &context).unwrap();
+
+        let history = get_state_rate_audit(&conn, guam.id).unwrap();
+        assert_eq!(history.len(), 4);
+        assert!(history[3].before_periods.is_none());
+        assert_eq!(history[0].before_periods, Some(vec![changed.clone()]));
+        assert_eq!(history[0].after_periods, Some(vec![]));
+        assert_eq!(history[1].before_periods.as_ref().unwrap()[0].rate, dec!(6));
+        assert_eq!(history[0].after.as_ref().unwrap().formula, RateFormula::fixed(dec!(6)));
+
+        // Reverting restores the periods one side of the entry recorded
+        let restored = history[0].before_periods.clone().unwrap();
+        replace_rate_periods(&conn, guam.id, &restored, &context).unwrap();
+        assert_eq!(get_rate_periods(&conn, guam.id).unwrap()[0].rate, dec!(7));
+        assert_eq!(get_state_rate_audit(&conn, guam.id).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_matters_and_calculations() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, None).unwrap();
+        let matter = Matter {
+            id: 0,
+            caption: "Smith v. Jones".to_string(),
+            docket_number: "1:24-cv-00123".to_string(),
+            court: "S.D.N.Y.".to_string(),
+            client: "Smith".to_string(),
+            judgment_date: "2024-01-15".to_string(),
+            principal: dec!(125000.50),
+            jurisdiction: "Federal".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            calculation_count: 0,
+        };
+        let matter_id = insert_matter(&conn, &matter).unwrap();
+
+        let request: CalcRequest = serde_json::from_value(serde_json::json!({
+            "judgment_date": "2024-01-15",
+            "is_federal": true,
+            "state": "",
+            "amount": "125000.50",
+            "from_date": "2024-01-15",
+            "to_date": "2024-07-15",
+        }))
+        .unwrap();
+        let response: CalcResponse = serde_json::from_value(serde_json::json!({
+            "rate": "4.79",
+            "days": 182,
+            "interest_amount": "2986.41",
+            "total_amount": "127986.91",
+            "rate_source": {
+                "description": "Federal (1-Year Treasury)",
+                "citation": "28 U.S.C. § 1961",
+                "source_url": "",
+                "effective_date": null,
+                "verification_note": "",
+            },
+            "disclaimer": "",
+            "periods": [],
+            "day_count": "actual_365",
+            "ledger": [],
+            "rate_segments": [],
+            "rate_steps": [],
+            "rate_window": null,
+            "rounding": { "stage": "per_period", "mode": "half_up" },
+            "per_diem": "16.41",
+            "schedule": [],
+            "warnings": [],
+        }))
+        .unwrap();
+        insert_calculation(&conn, matter_id, "Through July", &request, &response).unwrap();
+        let id = insert_calculation(&conn, matter_id, "Re-run", &request, &response).unwrap();
+
+        let matters = get_matters(&conn).unwrap();
+        assert_eq!(matters.len(), 1);
+        assert_eq!(matters[0].principal, dec!(125000.50));
+        assert_eq!(matters[0].calculation_count, 2);
+
+        let listed = get_calculations(&conn, matter_id).unwrap();
+        assert_eq!(listed[0].id, id);
+        assert_eq!(listed[1].name, "Through July");
+        assert_eq!(listed[1].to_date, "2024-07-15");
+        assert_eq!(listed[1].total_amount, dec!(127986.91));
+
+        rename_calculation(&conn, id, "Through judgment + 6 months").unwrap();
+        let saved = get_calculation(&conn, id).unwrap().unwrap();
+        assert_eq!(saved.name, "Through judgment + 6 months");
+        assert_eq!(saved.request.amount, dec!(125000.50));
+        assert_eq!(saved.response.days, 182);
+
+        delete_matter(&conn, matter_id).unwrap();
+        assert!(get_matter(&conn, matter_id).unwrap().is_none());
+        assert!(get_calculation(&conn, id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_observation_cache_coverage() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_fred_cache(&conn).unwrap();
+        let observation = |date: &str, value: &str| FredObservation {
+            realtime_start: "2024-02-01".to_string(),
+            realtime_end: "2024-02-01".to_string(),
+            date: date.to_string(),
+            value: value.to_string(),
+        };
+
+        cache_observations(
+            &conn,
+            "DGS1",
+            "2024-01-01",
+            "2024-01-07",
+            &[observation("2024-01-01", "."), observation("2024-01-02", "4.83")],
+        )
+        .unwrap();
+
+        assert!(is_range_cached(&conn, "DGS1", "2024-01-01", "2024-01-07").unwrap());
+        assert!(is_range_cached(&conn, "DGS1", "2024-01-06", "2024-01-07").unwrap());
+        assert!(!is_range_cached(&conn, "DGS1", "2024-01-06", "2024-01-08").unwrap());
+        assert!(!is_range_cached(&conn, "DPRIME", "2024-01-01", "2024-01-07").unwrap());
+
+        let cached = get_cached_observations(&conn, "DGS1", "2024-01-02", "2024-01-31").unwrap();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].value, "4.83");
+    }
+}
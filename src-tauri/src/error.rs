@@ -0,0 +1,179 @@
+use crate::rate_fetcher::FetchError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+// Stable identifiers the frontend and scripted callers can match on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidInput,
+    InvalidDate,
+    InvalidRateFormula,
+    DuplicateState,
+    // An import file that does not parse or has an invalid row
+    InvalidImport,
+    StateNotFound,
+    NotFound,
+    ApiKeyMissing,
+    ApiRequestFailed,
+    ApiResponseInvalid,
+    NoObservations,
+    DatabaseLocked,
+    DatabaseError,
+    // rates.db was written by a newer version of the app
+    SchemaTooNew,
+    ConfigError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    // The request was rejected; correcting the input fixes it
+    Validation,
+    NotFound,
+    // FRED could not be reached or answered unexpectedly
+    Network,
+    Database,
+    // The app's own settings are missing or unreadable
+    Configuration,
+}
+
+impl ErrorCode {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ErrorCode::InvalidInput
+            | ErrorCode::InvalidDate
+            | ErrorCode::InvalidRateFormula
+            | ErrorCode::DuplicateState
+            | ErrorCode::InvalidImport => ErrorCategory::Validation,
+            ErrorCode::StateNotFound | ErrorCode::NotFound => ErrorCategory::NotFound,
+            ErrorCode::ApiRequestFailed
+            | ErrorCode::ApiResponseInvalid
+            | ErrorCode::NoObservations => ErrorCategory::Network,
+            ErrorCode::DatabaseLocked | ErrorCode::DatabaseError | ErrorCode::SchemaTooNew => {
+                ErrorCategory::Database
+            }
+            ErrorCode::ApiKeyMissing | ErrorCode::ConfigError => ErrorCategory::Configuration,
+        }
+    }
+}
+
+// The error every command returns; serialized as-is across the IPC boundary
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+#[error("{message}")]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub category: ErrorCategory,
+    // Shown to the user
+    pub message: String,
+    // The underlying error, for support and logs
+    pub details: Option<String>,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self { code, category: code.category(), message: message.into(), details: None }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    // A date field that failed to parse
+    pub fn invalid_date(field: &str, error: chrono::ParseError) -> Self {
+        Self::new(ErrorCode::InvalidDate, format!("Invalid {}", field))
+            .with_details(error.to_string())
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    // Replaces the message with one saying what was being attempted
+    pub fn context(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(error: rusqlite::Error) -> Self {
+        let code = match &error {
+            rusqlite::Error::SqliteFailure(failure, _)
+                if matches!(
+                    failure.code,
+                    rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+                ) =>
+            {
+                ErrorCode::DatabaseLocked
+            }
+            rusqlite::Error::QueryReturnedNoRows => ErrorCode::NotFound,
+            _ => ErrorCode::DatabaseError,
+        };
+        let message = match code {
+            ErrorCode::DatabaseLocked => "The rate database is in use; try again",
+            ErrorCode::NotFound => "Record not found",
+            _ => "Database error",
+        };
+        AppError::new(code, message).with_details(error.to_string())
+    }
+}
+
+impl From<FetchError> for AppError {
+    fn from(error: FetchError) -> Self {
+        let details = error.to_string();
+        let (code, message) = match error {
+            FetchError::CacheError(e) => return AppError::from(e),
+            FetchError::RequestError(_) => (ErrorCode::ApiRequestFailed, "FRED request failed"),
+            FetchError::ParseError(_) | FetchError::DateError(_) => {
+                (ErrorCode::ApiResponseInvalid, "FRED returned an unexpected response")
+            }
+            FetchError::NoValidObservations => {
+                (ErrorCode::NoObservations, "FRED has no observations for the rate window")
+            }
+            FetchError::ApiKeyMissing => (
+                ErrorCode::ApiKeyMissing,
+                "API key not configured. Please set it in the settings.",
+            ),
+        };
+        AppError::new(code, message).with_details(details)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fetch_errors_keep_their_variant() {
+        let error = AppError::from(FetchError::ApiKeyMissing);
+        assert_eq!(error.code, ErrorCode::ApiKeyMissing);
+        assert_eq!(error.category, ErrorCategory::Configuration);
+
+        let error = AppError::from(FetchError::CacheError(rusqlite::Error::QueryReturnedNoRows));
+        assert_eq!(error.code, ErrorCode::NotFound);
+    }
+
+    #[test]
+    fn test_locked_database() {
+        let locked = rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+            None,
+        );
+        let error = AppError::from(locked);
+        assert_eq!(error.code, ErrorCode::DatabaseLocked);
+        assert_eq!(error.category, ErrorCategory::Database);
+        assert!(error.details.is_some());
+    }
+
+    #[test]
+    fn test_serialized_shape() {
+        let error = AppError::new(ErrorCode::StateNotFound, "State 'Guam' not found");
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "state_not_found");
+        assert_eq!(json["category"], "not_found");
+        assert_eq!(json["message"], "State 'Guam' not found");
+        assert!(json["details"].is_null());
+    }
+}
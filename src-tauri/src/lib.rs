@@ -3,12 +3,25 @@
 mod calculator;
 mod commands;
 mod db;
+mod error;
+mod lint;
 mod models;
 mod rate_fetcher;
+mod rate_table;
+mod seed;
 
 use commands::{
-    calculate, delete_state_rate_command, get_all_state_rates, get_api_key_configured,
-    set_api_key, update_state_rate_command, validate_api_key_command,
+    add_rate_period_command, calculate, create_matter_command, create_state_rate_command,
+    delete_calculation_command, delete_matter_command, delete_rate_period_command,
+    delete_state_rate_command, export_rate_table_command, get_all_state_rates,
+    get_api_key_configured, get_calculation_command, get_calculations_command, get_matters_command,
+    get_overdue_state_rates_command, get_rate_periods_command, get_seed_conflicts_command,
+    get_state_rate_history_command, get_trashed_state_rates_command, import_rate_table_command,
+    lint_rate_table_command, preview_rate_table_import_command, purge_state_rate_command,
+    rename_calculation_command, rerun_calculation_command, reset_rate_table_command,
+    resolve_seed_conflict_command, restore_state_rate_command, revert_state_rate_command,
+    save_calculation_command, set_api_key, update_matter_command, update_rate_period_command,
+    update_state_rate_command, validate_api_key_command, verify_state_rate_command,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -16,19 +29,52 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .setup(|app| {
-            // Initialize database
-            db::init_db(&app.handle()).expect("Failed to initialize database");
-            
             // Initialize logging
             env_logger::init();
-            
+
+            // Initialize database
+            db::init_db(app.handle()).expect("Failed to initialize database");
+
+            // Report contradictions in the table without waiting for the
+            // Database Manager to be opened
+            commands::publish_lint(app.handle());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             calculate,
             get_all_state_rates,
+            create_state_rate_command,
             update_state_rate_command,
             delete_state_rate_command,
+            get_trashed_state_rates_command,
+            restore_state_rate_command,
+            purge_state_rate_command,
+            get_state_rate_history_command,
+            verify_state_rate_command,
+            get_overdue_state_rates_command,
+            lint_rate_table_command,
+            revert_state_rate_command,
+            export_rate_table_command,
+            preview_rate_table_import_command,
+            import_rate_table_command,
+            get_seed_conflicts_command,
+            resolve_seed_conflict_command,
+            reset_rate_table_command,
+            get_rate_periods_command,
+            add_rate_period_command,
+            update_rate_period_command,
+            delete_rate_period_command,
+            get_matters_command,
+            create_matter_command,
+            update_matter_command,
+            delete_matter_command,
+            get_calculations_command,
+            save_calculation_command,
+            get_calculation_command,
+            rename_calculation_command,
+            delete_calculation_command,
+            rerun_calculation_command,
             set_api_key,
             get_api_key_configured,
             validate_api_key_command,
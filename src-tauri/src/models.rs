@@ -1,35 +1,848 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 
+// Money amounts and rates are exact decimals; they serialize as strings so no
+// precision is lost crossing the IPC boundary.
+
+// The largest rate, spread, floor or cap (percent) a formula may state; no
+// jurisdiction comes close, so anything larger is a typo
+pub const MAX_RATE_PERCENT: Decimal = dec!(50);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateRate {
     pub id: i32,
     pub state: String,
-    pub rate: f64,
-    pub is_variable: bool,
-    pub plus_percentage: f64,
-    pub update_frequency: String,
+    pub formula: RateFormula,
+    pub update_frequency: UpdateFrequency,
     pub last_update: String,
     pub notes: String,
+    #[serde(default)]
+    pub compounding: CompoundingMode,
+    #[serde(default)]
+    pub day_count: DayCount,
+    #[serde(default)]
+    pub rounding: RoundingPolicy,
+    #[serde(default)]
+    pub rate_date_rule: RateDateRule,
+    // Set while the row is in the trash
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+    // Where the rate comes from: the statute, a link to its text, the date the
+    // current rate took effect, and who checked it against the source and when
+    #[serde(default)]
+    pub citation: String,
+    #[serde(default)]
+    pub source_url: String,
+    #[serde(default)]
+    pub effective_date: Option<String>,
+    #[serde(default)]
+    pub verification_note: String,
+    // Who last checked the rate against its source, and when; empty until then
+    #[serde(default)]
+    pub verified_by: String,
+    #[serde(default)]
+    pub verified_on: Option<String>,
+    // When the rate is due to be checked again, following from `verified_on`
+    // and `update_frequency`; set when the row is written
+    #[serde(default)]
+    pub next_review: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+// How often a jurisdiction's rate must be re-verified against its source.
+// Exports and audit entries from before this was typed use the display names.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateFrequency {
+    #[serde(alias = "Weekly")]
+    Weekly,
+    #[serde(alias = "Monthly")]
+    Monthly,
+    #[serde(alias = "Quarterly")]
+    Quarterly,
+    #[serde(alias = "Semi-Annual")]
+    SemiAnnual,
+    #[default]
+    #[serde(alias = "Annual")]
+    Annual,
+}
+
+impl UpdateFrequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateFrequency::Weekly => "weekly",
+            UpdateFrequency::Monthly => "monthly",
+            UpdateFrequency::Quarterly => "quarterly",
+            UpdateFrequency::SemiAnnual => "semi_annual",
+            UpdateFrequency::Annual => "annual",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "weekly" => Some(UpdateFrequency::Weekly),
+            "monthly" => Some(UpdateFrequency::Monthly),
+            "quarterly" => Some(UpdateFrequency::Quarterly),
+            "semi_annual" => Some(UpdateFrequency::SemiAnnual),
+            "annual" => Some(UpdateFrequency::Annual),
+            _ => None,
+        }
+    }
+
+    // The date a rate verified on `verified_on` is next due for review
+    pub fn next_review(&self, verified_on: NaiveDate) -> NaiveDate {
+        let months = match self {
+            UpdateFrequency::Weekly => return verified_on + chrono::Days::new(7),
+            UpdateFrequency::Monthly => 1,
+            UpdateFrequency::Quarterly => 3,
+            UpdateFrequency::SemiAnnual => 6,
+            UpdateFrequency::Annual => 12,
+        };
+        verified_on + chrono::Months::new(months)
+    }
+}
+
+// Which date fixes a variable rate's index value. The federal rule averages the
+// calendar week preceding the judgment; the others take the latest observation
+// on January 1 of the judgment year, the first business day of the half-year
+// the judgment falls in, or the date of the verdict or of entry of judgment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateDateRule {
+    #[default]
+    PrecedingWeek,
+    JanuaryFirst,
+    HalfYearFirstBusinessDay,
+    VerdictDate,
+    EntryDate,
+}
+
+impl RateDateRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateDateRule::PrecedingWeek => "preceding_week",
+            RateDateRule::JanuaryFirst => "january_first",
+            RateDateRule::HalfYearFirstBusinessDay => "half_year_first_business_day",
+            RateDateRule::VerdictDate => "verdict_date",
+            RateDateRule::EntryDate => "entry_date",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "preceding_week" => Some(RateDateRule::PrecedingWeek),
+            "january_first" => Some(RateDateRule::JanuaryFirst),
+            "half_year_first_business_day" => Some(RateDateRule::HalfYearFirstBusinessDay),
+            "verdict_date" => Some(RateDateRule::VerdictDate),
+            "entry_date" => Some(RateDateRule::EntryDate),
+            _ => None,
+        }
+    }
+}
+
+// A jurisdiction's statutory rate in percent: the base (a fixed rate, an index,
+// or the greater or lesser of other formulas), optionally rounded to the
+// nearest `rounding_increment`, plus `spread`, then held between `floor` and
+// `cap`. Texas, for example, is the prime rate with a 5% floor and 15% cap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateFormula {
+    pub base: RateBase,
+    #[serde(default)]
+    pub rounding_increment: Option<Decimal>,
+    #[serde(default)]
+    pub spread: Decimal,
+    #[serde(default)]
+    pub floor: Option<Decimal>,
+    #[serde(default)]
+    pub cap: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RateBase {
+    Fixed { rate: Decimal },
+    Index { index: RateIndex },
+    GreaterOf { formulas: Vec<RateFormula> },
+    LesserOf { formulas: Vec<RateFormula> },
+}
+
+impl RateFormula {
+    pub fn fixed(rate: Decimal) -> Self {
+        Self::from_base(RateBase::Fixed { rate })
+    }
+
+    pub fn index(index: RateIndex, spread: Decimal) -> Self {
+        Self {
+            spread,
+            ..Self::from_base(RateBase::Index { index })
+        }
+    }
+
+    fn from_base(base: RateBase) -> Self {
+        Self {
+            base,
+            rounding_increment: None,
+            spread: Decimal::ZERO,
+            floor: None,
+            cap: None,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_floor(mut self, floor: Decimal) -> Self {
+        self.floor = Some(floor);
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_cap(mut self, cap: Decimal) -> Self {
+        self.cap = Some(cap);
+        self
+    }
+
+    // Whether the rate moves with a published index
+    pub fn is_variable(&self) -> bool {
+        !self.indexes().is_empty()
+    }
+
+    // The published indexes the formula needs, without duplicates
+    pub fn indexes(&self) -> Vec<RateIndex> {
+        let mut indexes = Vec::new();
+        self.collect_indexes(&mut indexes);
+        indexes
+    }
+
+    fn collect_indexes(&self, indexes: &mut Vec<RateIndex>) {
+        match &self.base {
+            RateBase::Fixed { .. } => {}
+            RateBase::Index { index } => {
+                if !indexes.contains(index) {
+                    indexes.push(*index);
+                }
+            }
+            RateBase::GreaterOf { formulas } | RateBase::LesserOf { formulas } => {
+                for formula in formulas {
+                    formula.collect_indexes(indexes);
+                }
+            }
+        }
+    }
+
+    // Reads like the statute, e.g. "Prime Rate + 2%, min 5%, max 15%"
+    pub fn describe(&self) -> String {
+        let base = match &self.base {
+            RateBase::Fixed { rate } => format!("{}%", rate.normalize()),
+            RateBase::Index { index } => index.label().to_string(),
+            RateBase::GreaterOf { formulas } => format!("greater of ({})", describe_all(formulas)),
+            RateBase::LesserOf { formulas } => format!("lesser of ({})", describe_all(formulas)),
+        };
+
+        let mut description = base;
+        if let Some(increment) = self.rounding_increment {
+            description = format!("{} rounded to {}%", description, increment.normalize());
+        }
+        if !self.spread.is_zero() {
+            let sign = if self.spread.is_sign_negative() { "-" } else { "+" };
+            description = format!("{} {} {}%", description, sign, self.spread.abs().normalize());
+        }
+        if let Some(floor) = self.floor {
+            description = format!("{}, min {}%", description, floor.normalize());
+        }
+        if let Some(cap) = self.cap {
+            description = format!("{}, max {}%", description, cap.normalize());
+        }
+        description
+    }
+
+    // Rejects negative or absurd rates and bounds that cannot both hold
+    pub fn validate(&self) -> Result<(), String> {
+        let in_range = |value: Decimal| value >= Decimal::ZERO && value <= MAX_RATE_PERCENT;
+        match &self.base {
+            RateBase::Fixed { rate } if !in_range(*rate) => {
+                return Err(format!("Rate {}% must be between 0% and {}%", rate, MAX_RATE_PERCENT))
+            }
+            RateBase::Fixed { .. } | RateBase::Index { .. } => {}
+            RateBase::GreaterOf { formulas } | RateBase::LesserOf { formulas } => {
+                if formulas.is_empty() {
+                    return Err("A greater-of or lesser-of rate needs at least one rate".to_string());
+                }
+                for formula in formulas {
+                    formula.validate()?;
+                }
+            }
+        }
+
+        if self.spread.abs() > MAX_RATE_PERCENT {
+            return Err(format!("Spread {}% exceeds {}%", self.spread, MAX_RATE_PERCENT));
+        }
+        if self.rounding_increment.is_some_and(|increment| increment <= Decimal::ZERO) {
+            return Err("Rounding increment must be positive".to_string());
+        }
+        for (name, bound) in [("Floor", self.floor), ("Cap", self.cap)] {
+            if let Some(bound) = bound.filter(|bound| !in_range(*bound)) {
+                return Err(format!(
+                    "{} {}% must be between 0% and {}%",
+                    name, bound, MAX_RATE_PERCENT
+                ));
+            }
+        }
+        if let (Some(floor), Some(cap)) = (self.floor, self.cap) {
+            if floor > cap {
+                return Err(format!("Floor {}% is above cap {}%", floor, cap));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn describe_all(formulas: &[RateFormula]) -> String {
+    formulas.iter().map(RateFormula::describe).collect::<Vec<_>>().join(", ")
+}
+
+// Published rates a variable rate can be indexed to, named by their FRED series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RateIndex {
+    // 1-Year Treasury Constant Maturity
+    #[serde(rename = "DGS1")]
+    Treasury1Year,
+    // Bank Prime Loan Rate
+    #[serde(rename = "DPRIME")]
+    Prime,
+    // Discount Window Primary Credit Rate
+    #[serde(rename = "DPCREDIT")]
+    DiscountRate,
+    // Federal Funds Effective Rate
+    #[serde(rename = "DFF")]
+    FedFunds,
+}
+
+impl RateIndex {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateIndex::Treasury1Year => "DGS1",
+            RateIndex::Prime => "DPRIME",
+            RateIndex::DiscountRate => "DPCREDIT",
+            RateIndex::FedFunds => "DFF",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "DGS1" => Some(RateIndex::Treasury1Year),
+            "DPRIME" => Some(RateIndex::Prime),
+            "DPCREDIT" => Some(RateIndex::DiscountRate),
+            "DFF" => Some(RateIndex::FedFunds),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RateIndex::Treasury1Year => "1-Year Treasury",
+            RateIndex::Prime => "Prime Rate",
+            RateIndex::DiscountRate => "Discount Rate",
+            RateIndex::FedFunds => "Federal Funds Rate",
+        }
+    }
+}
+
+// How often accrued interest is capitalized into the balance. `Anniversary`
+// compounds on each anniversary of the judgment date; the calendar-based modes
+// compound on January 1, January 1/July 1, or the first of each month.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompoundingMode {
+    #[default]
+    None,
+    Annual,
+    SemiAnnual,
+    Monthly,
+    Daily,
+    Anniversary,
+}
+
+impl CompoundingMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompoundingMode::None => "none",
+            CompoundingMode::Annual => "annual",
+            CompoundingMode::SemiAnnual => "semi_annual",
+            CompoundingMode::Monthly => "monthly",
+            CompoundingMode::Daily => "daily",
+            CompoundingMode::Anniversary => "anniversary",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(CompoundingMode::None),
+            "annual" => Some(CompoundingMode::Annual),
+            "semi_annual" => Some(CompoundingMode::SemiAnnual),
+            "monthly" => Some(CompoundingMode::Monthly),
+            "daily" => Some(CompoundingMode::Daily),
+            "anniversary" => Some(CompoundingMode::Anniversary),
+            _ => None,
+        }
+    }
+}
+
+// Day-count convention used to measure the accrual period. Actual/365 divides
+// actual days by 365 even in leap years; Actual/Actual divides the days falling
+// in each calendar year by that year's length; 30/360 treats every month as
+// 30 days and the year as 360.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DayCount {
+    #[default]
+    #[serde(rename = "actual_365")]
+    Actual365,
+    #[serde(rename = "actual_actual")]
+    ActualActual,
+    #[serde(rename = "actual_360")]
+    Actual360,
+    #[serde(rename = "thirty_360")]
+    Thirty360,
+}
+
+impl DayCount {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DayCount::Actual365 => "actual_365",
+            DayCount::ActualActual => "actual_actual",
+            DayCount::Actual360 => "actual_360",
+            DayCount::Thirty360 => "thirty_360",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "actual_365" => Some(DayCount::Actual365),
+            "actual_actual" => Some(DayCount::ActualActual),
+            "actual_360" => Some(DayCount::Actual360),
+            "thirty_360" => Some(DayCount::Thirty360),
+            _ => None,
+        }
+    }
+}
+
+// When interest is rounded to the cent. `PerDay` rounds the daily interest
+// (per diem) before multiplying by the days; `PerPeriod` rounds the interest of
+// each accrual period at payment and compounding dates; `Final` carries full
+// precision and rounds only the reported amounts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingStage {
+    PerDay,
+    #[default]
+    PerPeriod,
+    Final,
+}
+
+impl RoundingStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoundingStage::PerDay => "per_day",
+            RoundingStage::PerPeriod => "per_period",
+            RoundingStage::Final => "final",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "per_day" => Some(RoundingStage::PerDay),
+            "per_period" => Some(RoundingStage::PerPeriod),
+            "final" => Some(RoundingStage::Final),
+            _ => None,
+        }
+    }
+}
+
+// How a half cent is rounded: away from zero, or to the nearest even cent
+// (banker's rounding)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    #[default]
+    HalfUp,
+    HalfEven,
+}
+
+impl RoundingMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoundingMode::HalfUp => "half_up",
+            RoundingMode::HalfEven => "half_even",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "half_up" => Some(RoundingMode::HalfUp),
+            "half_even" => Some(RoundingMode::HalfEven),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundingPolicy {
+    pub stage: RoundingStage,
+    pub mode: RoundingMode,
+}
+
+// A statutory rate that applies to a jurisdiction from `effective_from` through
+// `effective_to` (inclusive), or indefinitely when `effective_to` is unset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateRatePeriod {
+    pub id: i32,
+    pub state_id: i32,
+    pub effective_from: String,
+    pub effective_to: Option<String>,
+    pub rate: Decimal,
+    pub notes: String,
+}
+
+// The kind of change an audit entry records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Insert,
+    Update,
+    // Moved to the trash
+    Delete,
+    Restore,
+    // Removed from the trash for good
+    Purge,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Insert => "insert",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+            AuditAction::Purge => "purge",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "insert" => Some(AuditAction::Insert),
+            "update" => Some(AuditAction::Update),
+            "delete" => Some(AuditAction::Delete),
+            "restore" => Some(AuditAction::Restore),
+            "purge" => Some(AuditAction::Purge),
+            _ => None,
+        }
+    }
+}
+
+// One change to a jurisdiction's row. `before` is unset for inserts and
+// `after` for deletes and purges; `state_rate_id` outlives the row it names.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateRateAudit {
+    pub id: i64,
+    pub state_rate_id: i32,
+    pub state: String,
+    pub action: AuditAction,
+    pub before: Option<StateRate>,
+    pub after: Option<StateRate>,
+    pub changed_at: String,
+    pub changed_by: String,
+    pub reason: Option<String>,
+    // The row's whole rate history either side of a change to its rate periods;
+    // unset for changes that left them alone
+    pub before_periods: Option<Vec<StateRatePeriod>>,
+    pub after_periods: Option<Vec<StateRatePeriod>>,
+}
+
+// Which side of an audit entry to restore
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSnapshot {
+    Before,
+    After,
+}
+
+// Who is making a change and why, recorded with it
+#[derive(Debug, Clone)]
+pub struct AuditContext {
+    pub changed_by: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CalcRequest {
     pub judgment_date: String,
+    // Needed only where the verdict date fixes the rate
+    #[serde(default)]
+    pub verdict_date: Option<String>,
     pub is_federal: bool,
     pub state: String,
-    pub amount: f64,
+    pub amount: Decimal,
     pub from_date: String,
     pub to_date: String,
+    // Overrides the jurisdiction's day-count convention when set
+    #[serde(default)]
+    pub day_count: Option<DayCount>,
+    #[serde(default)]
+    pub payments: Vec<Payment>,
+    #[serde(default)]
+    pub payment_allocation: PaymentAllocation,
+    #[serde(default)]
+    pub schedule_interval: ScheduleInterval,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payment {
+    pub date: String,
+    pub amount: Decimal,
+}
+
+// How a payment is split between unpaid interest and principal. The U.S. Rule
+// pays accrued interest first; pro rata splits the payment in proportion to the
+// outstanding interest and principal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentAllocation {
+    #[default]
+    UsRule,
+    ProRata,
+}
+
+// Granularity of the accrual schedule returned with a calculation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleInterval {
+    Daily,
+    #[default]
+    Monthly,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CalcResponse {
-    pub rate: f64,
+    pub rate: Decimal,
     pub days: i64,
-    pub interest_amount: f64,
-    pub total_amount: f64,
-    pub rate_source: String,
+    pub interest_amount: Decimal,
+    pub total_amount: Decimal,
+    pub rate_source: RateSource,
     pub disclaimer: String,
+    pub periods: Vec<InterestPeriod>,
+    pub day_count: DayCount,
+    pub ledger: Vec<LedgerEntry>,
+    pub rate_segments: Vec<RateSegment>,
+    // How the jurisdiction's formula produced `rate`, one step per operation
+    pub rate_steps: Vec<RateStep>,
+    // The index observations the rate was fixed from; unset for fixed rates
+    pub rate_window: Option<String>,
+    pub rounding: RoundingPolicy,
+    // Interest accruing per day after the end date
+    pub per_diem: Decimal,
+    pub schedule: Vec<ScheduleRow>,
+    // Fallbacks, stale data and suspicious inputs to review before relying on the result
+    pub warnings: Vec<CalcWarning>,
+}
+
+// The statutory basis of a calculation's rate, for citing it in a filing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateSource {
+    // The jurisdiction and how its rate is set, e.g. "Florida (Fixed: 4.75%)"
+    pub description: String,
+    pub citation: String,
+    pub source_url: String,
+    pub effective_date: Option<String>,
+    pub verification_note: String,
+}
+
+impl RateSource {
+    pub fn new(description: String, state_rate: &StateRate) -> Self {
+        Self {
+            description,
+            citation: state_rate.citation.clone(),
+            source_url: state_rate.source_url.clone(),
+            effective_date: state_rate.effective_date.clone(),
+            verification_note: state_rate.verification_note.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningCode {
+    // An index could not be fetched and the statutory default rate was used
+    DefaultRateUsed,
+    // FRED has not published the whole window and the cache held only part of it
+    PartialIndexData,
+    // FRED has not published the window and an observation from before it was used
+    StaleIndexData,
+    // FRED could not be reached or refused the request, so cached observations
+    // were used
+    IndexFetchFailed,
+    // Interest runs from before the judgment was entered
+    FromDateBeforeJudgment,
+    // The end date is in the future, so the interest is a projection
+    FutureEndDate,
+    // The jurisdiction's rate row is past its re-verification date
+    StaleRateRow,
+    // The jurisdiction's rate row has never been verified against its source
+    UnverifiedRateRow,
+    // A variable rate was migrated without the index it follows
+    MissingRateIndex,
+    // No row exists for the jurisdiction and statutory defaults were assumed
+    JurisdictionDefaults,
+    // The jurisdiction's rate row cites no statute
+    MissingCitation,
+    // The jurisdiction's rate row has fields that contradict each other
+    InconsistentRateRow,
+}
+
+impl WarningCode {
+    pub fn severity(&self) -> WarningSeverity {
+        match self {
+            WarningCode::DefaultRateUsed | WarningCode::MissingRateIndex => {
+                WarningSeverity::Critical
+            }
+            WarningCode::PartialIndexData
+            | WarningCode::StaleIndexData
+            | WarningCode::IndexFetchFailed
+            | WarningCode::FromDateBeforeJudgment
+            | WarningCode::StaleRateRow
+            | WarningCode::UnverifiedRateRow
+            | WarningCode::MissingCitation
+            | WarningCode::InconsistentRateRow => WarningSeverity::Warning,
+            WarningCode::FutureEndDate | WarningCode::JurisdictionDefaults => WarningSeverity::Info,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalcWarning {
+    pub code: WarningCode,
+    pub severity: WarningSeverity,
+    pub message: String,
+}
+
+impl CalcWarning {
+    pub fn new(code: WarningCode, message: impl Into<String>) -> Self {
+        Self { code, severity: code.severity(), message: message.into() }
+    }
+}
+
+// A judgment being tracked, with the case details its calculations are filed
+// under. `jurisdiction` names a state_rates row, or "Federal".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Matter {
+    #[serde(default)]
+    pub id: i64,
+    pub caption: String,
+    #[serde(default)]
+    pub docket_number: String,
+    #[serde(default)]
+    pub court: String,
+    #[serde(default)]
+    pub client: String,
+    pub judgment_date: String,
+    pub principal: Decimal,
+    pub jurisdiction: String,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+    // Saved calculations; set when read, ignored when written
+    #[serde(default)]
+    pub calculation_count: i64,
+}
+
+// A calculation saved against a matter: the request as entered and the result
+// it produced at the time
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedCalculation {
+    pub id: i64,
+    pub matter_id: i64,
+    pub name: String,
+    pub request: CalcRequest,
+    pub response: CalcResponse,
+    pub calculated_at: String,
+}
+
+// A saved calculation as listed under its matter, without the full result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalculationSummary {
+    pub id: i64,
+    pub matter_id: i64,
+    pub name: String,
+    pub calculated_at: String,
+    pub from_date: String,
+    pub to_date: String,
+    pub rate: Decimal,
+    pub total_amount: Decimal,
+}
+
+// One operation in evaluating a rate formula and the rate (percent) after it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateStep {
+    pub description: String,
+    pub rate: Decimal,
+}
+
+// One accrual segment of a calculation; compounded calculations capitalize
+// the interest of each period into the opening balance of the next.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestPeriod {
+    pub start_date: String,
+    pub end_date: String,
+    pub days: i64,
+    pub opening_balance: Decimal,
+    pub interest: Decimal,
+    pub closing_balance: Decimal,
+}
+
+// The part of an accrual charged at a single rate. `rate` is a percentage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateSegment {
+    pub start_date: String,
+    pub end_date: String,
+    pub days: i64,
+    pub rate: Decimal,
+    pub interest: Decimal,
+}
+
+// One row of the running-balance table: the interest accrued since the previous
+// row, the payment received on `date` and how it was applied, and the balances
+// left afterwards. The last row carries the balance as of the end date.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub date: String,
+    pub days: i64,
+    pub interest_accrued: Decimal,
+    pub payment: Decimal,
+    pub applied_to_interest: Decimal,
+    pub applied_to_principal: Decimal,
+    pub unpaid_interest: Decimal,
+    pub principal_balance: Decimal,
+    pub balance: Decimal,
+}
+
+// The balance as of `date` in the accrual schedule: interest accrued and
+// payments received since the previous row, interest accrued since the start
+// of the calculation, and the amount owed including unpaid interest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRow {
+    pub date: String,
+    pub days: i64,
+    pub interest: Decimal,
+    pub payments: Decimal,
+    pub cumulative_interest: Decimal,
+    pub balance: Decimal,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -50,21 +863,36 @@ pub struct FredObservation {
 impl StateRate {
     pub fn new(
         state: String,
-        rate: f64,
-        is_variable: bool,
-        plus_percentage: f64,
-        update_frequency: String,
+        formula: RateFormula,
+        update_frequency: UpdateFrequency,
         notes: String,
     ) -> Self {
         Self {
             id: 0,
             state,
-            rate,
-            is_variable,
-            plus_percentage,
+            formula,
             update_frequency,
             last_update: chrono::Local::now().format("%Y-%m-%d").to_string(),
             notes,
+            compounding: CompoundingMode::None,
+            day_count: DayCount::Actual365,
+            rounding: RoundingPolicy::default(),
+            rate_date_rule: RateDateRule::PrecedingWeek,
+            deleted_at: None,
+            citation: String::new(),
+            source_url: String::new(),
+            effective_date: None,
+            verification_note: String::new(),
+            verified_by: String::new(),
+            verified_on: None,
+            next_review: None,
         }
     }
+
+    // `next_review` for the row as it stands; unset while it is unverified
+    pub fn review_due(&self) -> Option<String> {
+        let verified_on = NaiveDate::parse_from_str(self.verified_on.as_deref()?, "%Y-%m-%d").ok()?;
+        let due = self.update_frequency.next_review(verified_on);
+        Some(due.format("%Y-%m-%d").to_string())
+    }
 }
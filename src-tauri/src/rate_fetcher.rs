@@ -1,7 +1,11 @@
-use crate::models::{FredResponse, FredObservation};
-use chrono::{Datelike, Duration, NaiveDate};
+use crate::db::{cache_observations, get_cached_observations, is_range_cached};
+use crate::models::{FredResponse, FredObservation, RateDateRule, RateIndex};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use rusqlite::Connection;
 use thiserror::Error;
 use reqwest::blocking::Client;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 
 #[derive(Error, Debug)]
 pub enum FetchError {
@@ -15,26 +19,212 @@ pub enum FetchError {
     NoValidObservations,
     #[error("API key not configured")]
     ApiKeyMissing,
+    #[error("Observation cache error: {0}")]
+    CacheError(#[from] rusqlite::Error),
 }
 
-pub fn get_federal_rate(
-    judgment_date: NaiveDate,
+// The observations of an index that fix a rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObservationWindow {
+    // Average of the observations between the dates, inclusive
+    Average(NaiveDate, NaiveDate),
+    // The latest observation on or before the date
+    AsOf(NaiveDate),
+}
+
+impl ObservationWindow {
+    // The dates to fetch from FRED
+    fn range(&self) -> (NaiveDate, NaiveDate) {
+        match *self {
+            ObservationWindow::Average(start_date, end_date) => (start_date, end_date),
+            // Look back far enough to cover weekends and holidays
+            ObservationWindow::AsOf(date) => (date - Duration::days(AS_OF_LOOKBACK_DAYS), date),
+        }
+    }
+
+    fn resolve(&self, observations: Vec<FredObservation>) -> Result<Decimal, FetchError> {
+        match self {
+            ObservationWindow::Average(..) => calculate_average_rate(observations),
+            ObservationWindow::AsOf(_) => latest_rate(observations),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            ObservationWindow::Average(start, end) => format!(
+                "Average of observations {} to {}",
+                start.format("%Y-%m-%d"),
+                end.format("%Y-%m-%d")
+            ),
+            ObservationWindow::AsOf(date) => {
+                format!("Latest observation on or before {}", date.format("%Y-%m-%d"))
+            }
+        }
+    }
+}
+
+// Resolves a jurisdiction's rule to the observations that fix its rate. `date`
+// is the verdict date under the verdict rule and the judgment date otherwise.
+pub fn observation_window(rule: RateDateRule, date: NaiveDate) -> ObservationWindow {
+    match rule {
+        RateDateRule::PrecedingWeek => {
+            let (start_date, end_date) = get_preceding_week(date);
+            ObservationWindow::Average(start_date, end_date)
+        }
+        RateDateRule::JanuaryFirst => ObservationWindow::AsOf(
+            NaiveDate::from_ymd_opt(date.year(), 1, 1).expect("January 1 is always valid"),
+        ),
+        RateDateRule::HalfYearFirstBusinessDay => {
+            let month = if date.month() < 7 { 1 } else { 7 };
+            let first = NaiveDate::from_ymd_opt(date.year(), month, 1)
+                .expect("the first of the month is always valid");
+            ObservationWindow::AsOf(first_business_day_from(first))
+        }
+        RateDateRule::VerdictDate | RateDateRule::EntryDate => ObservationWindow::AsOf(date),
+    }
+}
+
+// Skips weekends and New Year's Day
+fn first_business_day_from(date: NaiveDate) -> NaiveDate {
+    date.iter_days()
+        .find(|day| {
+            let weekend = matches!(day.weekday(), Weekday::Sat | Weekday::Sun);
+            let new_years_day = day.month() == 1 && day.day() == 1;
+            !weekend && !new_years_day
+        })
+        .expect("a business day always follows")
+}
+
+// The index's FRED series resolved over the observation window
+pub fn get_index_rate(
+    conn: &Connection,
+    index: RateIndex,
+    window: ObservationWindow,
     api_key: &str,
-) -> Result<f64, FetchError> {
-    if api_key.is_empty() {
-        return Err(FetchError::ApiKeyMissing);
+) -> Result<IndexRate, FetchError> {
+    let series_id = index.as_str();
+    let (start_date, end_date) = window.range();
+    let start = start_date.format("%Y-%m-%d").to_string();
+    let end = end_date.format("%Y-%m-%d").to_string();
+
+    // Read the cache first; historical observations do not change
+    if is_range_cached(conn, series_id, &start, &end)? {
+        let observations = get_cached_observations(conn, series_id, &start, &end)?;
+        return Ok(IndexRate {
+            rate: window.resolve(observations)?,
+            source: ObservationSource::Cache,
+        });
     }
 
-    // Calculate the preceding week (Monday to Sunday)
-    let (start_date, end_date) = get_preceding_week(judgment_date);
-    
-    // Fetch data from FRED API
-    let observations = fetch_fred_data(api_key, start_date, end_date)?;
-    
-    // Calculate average rate
-    calculate_average_rate(observations)
+    let fetched = if api_key.is_empty() {
+        Err(FetchError::ApiKeyMissing)
+    } else {
+        fetch_fred_data(api_key, series_id, start_date, end_date)
+    };
+
+    let (error, cause) = match fetched {
+        Ok(observations) => {
+            // Observations through yesterday are final; today's may not be published yet
+            let yesterday = chrono::Local::now().date_naive() - Duration::days(1);
+            let covered_through = end_date.min(yesterday).format("%Y-%m-%d").to_string();
+            cache_observations(conn, series_id, &start, &covered_through, &observations)?;
+            match window.resolve(observations) {
+                Ok(rate) => return Ok(IndexRate { rate, source: ObservationSource::Fred }),
+                // FRED has not published for the window, e.g. a holiday week
+                Err(error) => (error, FallbackCause::NotPublished),
+            }
+        }
+        Err(error) => {
+            let cause = FallbackCause::FetchFailed(error.to_string());
+            (error, cause)
+        }
+    };
+
+    // Use what the cache holds for the window, or failing that the latest
+    // observation cached shortly before it
+    let partial = get_cached_observations(conn, series_id, &start, &end)?;
+    if let Ok(rate) = window.resolve(partial) {
+        return Ok(IndexRate { rate, source: ObservationSource::PartialCache { cause } });
+    }
+    let oldest = (start_date - Duration::days(MAX_STALE_DAYS)).format("%Y-%m-%d").to_string();
+    let earlier = get_cached_observations(conn, series_id, &oldest, &end)?;
+    match latest_observation(&earlier) {
+        Some((observed, rate)) => {
+            Ok(IndexRate { rate, source: ObservationSource::StaleCache { observed, cause } })
+        }
+        None => Err(error),
+    }
+}
+
+// An index value and where its observations came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexRate {
+    pub rate: Decimal,
+    pub source: ObservationSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObservationSource {
+    // Fetched from FRED
+    Fred,
+    // Read from the cache, which held the whole window
+    Cache,
+    // FRED's observations could not be used and the cache held only part of the window
+    PartialCache { cause: FallbackCause },
+    // FRED's observations could not be used and the cache held nothing in the
+    // window, so the latest observation before it, from `observed`, was used
+    StaleCache { observed: NaiveDate, cause: FallbackCause },
+}
+
+// Why the cache stood in for FRED
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FallbackCause {
+    // FRED answered but has published nothing for the window yet
+    NotPublished,
+    // FRED could not be asked or did not answer: no API key, a network or an
+    // HTTP error. Holds the error.
+    FetchFailed(String),
+}
+
+impl ObservationSource {
+    pub fn describe(&self) -> String {
+        match self {
+            ObservationSource::Fred => "fetched from FRED".to_string(),
+            ObservationSource::Cache => "from local cache".to_string(),
+            ObservationSource::PartialCache { .. } => {
+                "from local cache, window incomplete".to_string()
+            }
+            ObservationSource::StaleCache { observed, .. } => format!(
+                "from the latest cached observation before the window, dated {}",
+                observed.format("%Y-%m-%d")
+            ),
+        }
+    }
+
+    // Why FRED was not used, for the fallback sources
+    pub fn cause(&self) -> Option<&FallbackCause> {
+        match self {
+            ObservationSource::PartialCache { cause }
+            | ObservationSource::StaleCache { cause, .. } => Some(cause),
+            ObservationSource::Fred | ObservationSource::Cache => None,
+        }
+    }
+}
+
+impl FallbackCause {
+    pub fn describe(&self) -> String {
+        match self {
+            FallbackCause::NotPublished => "FRED has not published data for the window".to_string(),
+            FallbackCause::FetchFailed(error) => format!("Fetching from FRED failed ({})", error),
+        }
+    }
 }
 
+const AS_OF_LOOKBACK_DAYS: i64 = 14;
+
+// How far before the window a cached observation may stand in for it
+const MAX_STALE_DAYS: i64 = 31;
+
 // Calculates the Monday to Sunday of the week before the judgment date
 fn get_preceding_week(judgment_date: NaiveDate) -> (NaiveDate, NaiveDate) {
     let days_since_monday = judgment_date.weekday().num_days_from_monday();
@@ -46,10 +236,10 @@ fn get_preceding_week(judgment_date: NaiveDate) -> (NaiveDate, NaiveDate) {
 
 fn fetch_fred_data(
     api_key: &str,
+    series_id: &str,
     start_date: NaiveDate,
     end_date: NaiveDate,
 ) -> Result<Vec<FredObservation>, FetchError> {
-    let series_id = "DGS1"; // 1-Year Treasury Constant Maturity Rate
     let url = format!(
         "https://api.stlouisfed.org/fred/series/observations?series_id={}&api_key={}&file_type=json&observation_start={}&observation_end={}",
         series_id,
@@ -71,28 +261,48 @@ fn fetch_fred_data(
     Ok(fred_response.observations)
 }
 
-fn calculate_average_rate(observations: Vec<FredObservation>) -> Result<f64, FetchError> {
-    let valid_rates: Vec<f64> = observations
+fn calculate_average_rate(observations: Vec<FredObservation>) -> Result<Decimal, FetchError> {
+    let valid_rates: Vec<Decimal> = observations
         .iter()
         .filter(|obs| obs.value != ".")
-        .filter_map(|obs| obs.value.parse::<f64>().ok())
+        .filter_map(|obs| obs.value.parse::<Decimal>().ok())
         .collect();
     
     if valid_rates.is_empty() {
         return Err(FetchError::NoValidObservations);
     }
     
-    let sum: f64 = valid_rates.iter().sum();
-    let average = sum / valid_rates.len() as f64;
+    let sum: Decimal = valid_rates.iter().sum();
+    let average = sum / Decimal::from(valid_rates.len());
     
     // Convert from percentage to decimal
-    Ok(average / 100.0)
+    Ok(average / Decimal::ONE_HUNDRED)
+}
+
+fn latest_rate(observations: Vec<FredObservation>) -> Result<Decimal, FetchError> {
+    let (_, rate) = latest_observation(&observations).ok_or(FetchError::NoValidObservations)?;
+    Ok(rate)
+}
+
+// The date and rate of the latest observation with a value
+fn latest_observation(observations: &[FredObservation]) -> Option<(NaiveDate, Decimal)> {
+    let (date, value) = observations
+        .iter()
+        .filter(|obs| obs.value != ".")
+        .filter_map(|obs| {
+            let date = NaiveDate::parse_from_str(&obs.date, "%Y-%m-%d").ok()?;
+            Some((date, obs.value.parse::<Decimal>().ok()?))
+        })
+        .max_by_key(|(date, _)| *date)?;
+
+    // Convert from percentage to decimal
+    Some((date, value / Decimal::ONE_HUNDRED))
 }
 
 // Helper function to get a cached/default rate if API fails
-pub fn get_fallback_federal_rate() -> f64 {
+pub fn get_fallback_federal_rate() -> Decimal {
     // Default to 5% if we can't fetch the actual rate
-    0.05
+    dec!(0.05)
 }
 
 // Function to validate API key by making a test request
@@ -143,4 +353,133 @@ mod tests {
         assert_eq!(start, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
         assert_eq!(end, NaiveDate::from_ymd_opt(2024, 1, 7).unwrap());
     }
+
+    #[test]
+    fn test_calculate_average_rate_is_exact() {
+        let observation = |date: &str, value: &str| FredObservation {
+            realtime_start: date.to_string(),
+            realtime_end: date.to_string(),
+            date: date.to_string(),
+            value: value.to_string(),
+        };
+        let observations = vec![
+            observation("2024-01-01", "."),
+            observation("2024-01-02", "4.83"),
+            observation("2024-01-03", "4.85"),
+        ];
+
+        assert_eq!(calculate_average_rate(observations).unwrap(), dec!(0.0484));
+    }
+
+    #[test]
+    fn test_observation_window_rules() {
+        let judgment_date = NaiveDate::from_ymd_opt(2023, 8, 16).unwrap();
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        assert_eq!(
+            observation_window(RateDateRule::PrecedingWeek, judgment_date),
+            ObservationWindow::Average(date(2023, 8, 7), date(2023, 8, 13))
+        );
+        assert_eq!(
+            observation_window(RateDateRule::JanuaryFirst, judgment_date),
+            ObservationWindow::AsOf(date(2023, 1, 1))
+        );
+        // July 1, 2023 was a Saturday
+        assert_eq!(
+            observation_window(RateDateRule::HalfYearFirstBusinessDay, judgment_date),
+            ObservationWindow::AsOf(date(2023, 7, 3))
+        );
+        // January 1, 2023 was a Sunday
+        assert_eq!(
+            observation_window(RateDateRule::HalfYearFirstBusinessDay, date(2023, 3, 1)),
+            ObservationWindow::AsOf(date(2023, 1, 2))
+        );
+        assert_eq!(
+            observation_window(RateDateRule::EntryDate, judgment_date),
+            ObservationWindow::AsOf(judgment_date)
+        );
+    }
+
+    #[test]
+    fn test_latest_rate_skips_missing_observations() {
+        let observation = |date: &str, value: &str| FredObservation {
+            realtime_start: date.to_string(),
+            realtime_end: date.to_string(),
+            date: date.to_string(),
+            value: value.to_string(),
+        };
+        let observations = vec![
+            observation("2023-12-29", "8.50"),
+            observation("2023-12-28", "8.25"),
+            observation("2024-01-01", "."),
+        ];
+
+        assert_eq!(latest_rate(observations).unwrap(), dec!(0.085));
+    }
+
+    #[test]
+    fn test_get_index_rate_offline_uses_cache() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init_fred_cache(&conn).unwrap();
+        let observation = |date: &str, value: &str| FredObservation {
+            realtime_start: date.to_string(),
+            realtime_end: date.to_string(),
+            date: date.to_string(),
+            value: value.to_string(),
+        };
+        cache_observations(
+            &conn,
+            "DGS1",
+            "2024-01-01",
+            "2024-01-07",
+            &[observation("2024-01-02", "4.83"), observation("2024-01-03", "4.85")],
+        )
+        .unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+
+        let cached = get_index_rate(
+            &conn,
+            RateIndex::Treasury1Year,
+            ObservationWindow::Average(date(1), date(7)),
+            "",
+        )
+        .unwrap();
+        assert_eq!(cached, IndexRate { rate: dec!(0.0484), source: ObservationSource::Cache });
+
+        // Without an API key the week after falls back to the last cached observation
+        let stale = get_index_rate(
+            &conn,
+            RateIndex::Treasury1Year,
+            ObservationWindow::Average(date(8), date(14)),
+            "",
+        )
+        .unwrap();
+        let cause = FallbackCause::FetchFailed(FetchError::ApiKeyMissing.to_string());
+        assert_eq!(cause.describe(), "Fetching from FRED failed (API key not configured)");
+        assert_eq!(
+            stale,
+            IndexRate {
+                rate: dec!(0.0485),
+                source: ObservationSource::StaleCache { observed: date(3), cause }
+            }
+        );
+
+        // Observations more than a month before the window are too old to stand in
+        let too_old = get_index_rate(
+            &conn,
+            RateIndex::Treasury1Year,
+            ObservationWindow::AsOf(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()),
+            "",
+        );
+        assert!(matches!(too_old, Err(FetchError::ApiKeyMissing)));
+
+        // Nothing cached for the series at all
+        let missing = get_index_rate(
+            &conn,
+            RateIndex::Prime,
+            ObservationWindow::AsOf(date(2)),
+            "",
+        );
+        assert!(matches!(missing, Err(FetchError::ApiKeyMissing)));
+    }
 }
@@ -0,0 +1,454 @@
+use crate::models::{
+    CompoundingMode, DayCount, RateBase, RateDateRule, RateFormula, RoundingMode, RoundingPolicy,
+    RoundingStage, StateRate, StateRatePeriod, UpdateFrequency,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+// Bumped when the file layout changes incompatibly
+const FILE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TableFormat {
+    Json,
+    Csv,
+}
+
+// One jurisdiction as it travels between installs: the row without its
+// database id, with its effective-dated rate history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateTableRow {
+    pub state: String,
+    pub formula: RateFormula,
+    pub update_frequency: UpdateFrequency,
+    pub last_update: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub compounding: CompoundingMode,
+    #[serde(default)]
+    pub day_count: DayCount,
+    #[serde(default)]
+    pub rounding: RoundingPolicy,
+    #[serde(default)]
+    pub rate_date_rule: RateDateRule,
+    #[serde(default)]
+    pub citation: String,
+    #[serde(default)]
+    pub source_url: String,
+    #[serde(default)]
+    pub effective_date: Option<String>,
+    #[serde(default)]
+    pub verification_note: String,
+    #[serde(default)]
+    pub verified_by: String,
+    #[serde(default)]
+    pub verified_on: Option<String>,
+    #[serde(default)]
+    pub rate_periods: Vec<RatePeriodRow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RatePeriodRow {
+    pub effective_from: String,
+    pub effective_to: Option<String>,
+    pub rate: Decimal,
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RateTableFile {
+    version: u32,
+    exported_at: String,
+    state_rates: Vec<RateTableRow>,
+}
+
+// A spreadsheet-friendly row: the formula is a plain number for a fixed rate
+// and JSON otherwise; rate periods are a JSON array
+#[derive(Debug, Serialize, Deserialize)]
+struct CsvRow {
+    state: String,
+    formula: String,
+    update_frequency: UpdateFrequency,
+    last_update: String,
+    #[serde(default)]
+    notes: String,
+    compounding: CompoundingMode,
+    day_count: DayCount,
+    rounding_stage: RoundingStage,
+    rounding_mode: RoundingMode,
+    rate_date_rule: RateDateRule,
+    #[serde(default)]
+    citation: String,
+    #[serde(default)]
+    source_url: String,
+    #[serde(default)]
+    effective_date: Option<String>,
+    #[serde(default)]
+    verification_note: String,
+    #[serde(default)]
+    verified_by: String,
+    #[serde(default)]
+    verified_on: Option<String>,
+    #[serde(default)]
+    rate_periods: String,
+}
+
+// What an import would do to the current table, matching rows by name
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ImportPreview {
+    pub added: Vec<String>,
+    pub changed: Vec<RowChange>,
+    pub removed: Vec<String>,
+    pub unchanged: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RowChange {
+    pub state: String,
+    // Names of the fields that differ
+    pub fields: Vec<String>,
+}
+
+impl RateTableRow {
+    pub fn new(state_rate: &StateRate, periods: &[StateRatePeriod]) -> Self {
+        Self {
+            state: state_rate.state.clone(),
+            formula: state_rate.formula.clone(),
+            update_frequency: state_rate.update_frequency,
+            last_update: state_rate.last_update.clone(),
+            notes: state_rate.notes.clone(),
+            compounding: state_rate.compounding,
+            day_count: state_rate.day_count,
+            rounding: state_rate.rounding,
+            rate_date_rule: state_rate.rate_date_rule,
+            citation: state_rate.citation.clone(),
+            source_url: state_rate.source_url.clone(),
+            effective_date: state_rate.effective_date.clone(),
+            verification_note: state_rate.verification_note.clone(),
+            verified_by: state_rate.verified_by.clone(),
+            verified_on: state_rate.verified_on.clone(),
+            rate_periods: periods
+                .iter()
+                .map(|period| RatePeriodRow {
+                    effective_from: period.effective_from.clone(),
+                    effective_to: period.effective_to.clone(),
+                    rate: period.rate,
+                    notes: period.notes.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    // The row as stored under `id`, and its rate periods
+    pub fn to_state_rate(&self, id: i32) -> (StateRate, Vec<StateRatePeriod>) {
+        let state_rate = StateRate {
+            id,
+            state: self.state.clone(),
+            formula: self.formula.clone(),
+            update_frequency: self.update_frequency,
+            last_update: self.last_update.clone(),
+            notes: self.notes.clone(),
+            compounding: self.compounding,
+            day_count: self.day_count,
+            rounding: self.rounding,
+            rate_date_rule: self.rate_date_rule,
+            deleted_at: None,
+            citation: self.citation.clone(),
+            source_url: self.source_url.clone(),
+            effective_date: self.effective_date.clone(),
+            verification_note: self.verification_note.clone(),
+            verified_by: self.verified_by.clone(),
+            verified_on: self.verified_on.clone(),
+            next_review: None,
+        };
+        let periods = self
+            .rate_periods
+            .iter()
+            .map(|period| StateRatePeriod {
+                id: 0,
+                state_id: id,
+                effective_from: period.effective_from.clone(),
+                effective_to: period.effective_to.clone(),
+                rate: period.rate,
+                notes: period.notes.clone(),
+            })
+            .collect();
+        (state_rate, periods)
+    }
+
+    // Names of the fields that differ from `other`. Rows are matched ignoring case, so a
+    // change of capitalisation is a change of name.
+    fn changed_fields(&self, other: &RateTableRow) -> Vec<String> {
+        let fields = [
+            ("state", self.state != other.state),
+            ("formula", self.formula != other.formula),
+            ("update_frequency", self.update_frequency != other.update_frequency),
+            ("last_update", self.last_update != other.last_update),
+            ("notes", self.notes != other.notes),
+            ("compounding", self.compounding != other.compounding),
+            ("day_count", self.day_count != other.day_count),
+            ("rounding", self.rounding != other.rounding),
+            ("rate_date_rule", self.rate_date_rule != other.rate_date_rule),
+            ("citation", self.citation != other.citation),
+            ("source_url", self.source_url != other.source_url),
+            ("effective_date", self.effective_date != other.effective_date),
+            ("verification_note", self.verification_note != other.verification_note),
+            ("verified_by", self.verified_by != other.verified_by),
+            ("verified_on", self.verified_on != other.verified_on),
+            ("rate_periods", self.rate_periods != other.rate_periods),
+        ];
+        fields
+            .iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+}
+
+pub fn export_rows(rows: &[RateTableRow], format: TableFormat) -> Result<String, String> {
+    match format {
+        TableFormat::Json => {
+            let file = RateTableFile {
+                version: FILE_VERSION,
+                exported_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                state_rates: rows.to_vec(),
+            };
+            serde_json::to_string_pretty(&file).map_err(|e| e.to_string())
+        }
+        TableFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for row in rows {
+                let periods = if row.rate_periods.is_empty() {
+                    String::new()
+                } else {
+                    serde_json::to_string(&row.rate_periods).map_err(|e| e.to_string())?
+                };
+                writer
+                    .serialize(CsvRow {
+                        state: row.state.clone(),
+                        formula: format_formula(&row.formula)?,
+                        update_frequency: row.update_frequency,
+                        last_update: row.last_update.clone(),
+                        notes: row.notes.clone(),
+                        compounding: row.compounding,
+                        day_count: row.day_count,
+                        rounding_stage: row.rounding.stage,
+                        rounding_mode: row.rounding.mode,
+                        rate_date_rule: row.rate_date_rule,
+                        citation: row.citation.clone(),
+                        source_url: row.source_url.clone(),
+                        effective_date: row.effective_date.clone(),
+                        verification_note: row.verification_note.clone(),
+                        verified_by: row.verified_by.clone(),
+                        verified_on: row.verified_on.clone(),
+                        rate_periods: periods,
+                    })
+                    .map_err(|e| e.to_string())?;
+            }
+            let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+            String::from_utf8(bytes).map_err(|e| e.to_string())
+        }
+    }
+}
+
+// Parses an exported file; errors name the row that failed
+pub fn parse_rows(contents: &str, format: TableFormat) -> Result<Vec<RateTableRow>, String> {
+    let mut rows = match format {
+        TableFormat::Json => {
+            let file: RateTableFile = serde_json::from_str(contents)
+                .map_err(|e| format!("Not a rate table export: {}", e))?;
+            if file.version > FILE_VERSION {
+                return Err(format!(
+                    "The file is format version {}; this app reads up to version {}",
+                    file.version, FILE_VERSION
+                ));
+            }
+            file.state_rates
+        }
+        TableFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(contents.as_bytes());
+            let mut rows = Vec::new();
+            for (index, record) in reader.deserialize::<CsvRow>().enumerate() {
+                // Row 1 is the header
+                let line = index + 2;
+                let record = record.map_err(|e| format!("Row {}: {}", line, e))?;
+                let rate_periods = if record.rate_periods.trim().is_empty() {
+                    Vec::new()
+                } else {
+                    serde_json::from_str(&record.rate_periods)
+                        .map_err(|e| format!("Row {}: invalid rate periods: {}", line, e))?
+                };
+                rows.push(RateTableRow {
+                    formula: parse_formula(&record.formula)
+                        .map_err(|e| format!("Row {}: invalid formula: {}", line, e))?,
+                    state: record.state,
+                    update_frequency: record.update_frequency,
+                    last_update: record.last_update,
+                    notes: record.notes,
+                    compounding: record.compounding,
+                    day_count: record.day_count,
+                    rounding: RoundingPolicy {
+                        stage: record.rounding_stage,
+                        mode: record.rounding_mode,
+                    },
+                    rate_date_rule: record.rate_date_rule,
+                    citation: record.citation,
+                    source_url: record.source_url,
+                    effective_date: record.effective_date,
+                    verification_note: record.verification_note,
+                    verified_by: record.verified_by,
+                    verified_on: record.verified_on,
+                    rate_periods,
+                });
+            }
+            rows
+        }
+    };
+
+    let mut seen = HashSet::new();
+    for row in &mut rows {
+        row.state = row.state.trim().to_string();
+        if !seen.insert(row.state.to_lowercase()) {
+            return Err(format!("'{}' appears more than once", row.state));
+        }
+    }
+    Ok(rows)
+}
+
+// Compares the incoming rows with the current table, matching names ignoring case
+pub fn diff_rows(current: &[RateTableRow], incoming: &[RateTableRow]) -> ImportPreview {
+    let key = |row: &RateTableRow| row.state.trim().to_lowercase();
+    let existing: HashMap<String, &RateTableRow> =
+        current.iter().map(|row| (key(row), row)).collect();
+    let incoming_keys: Vec<String> = incoming.iter().map(key).collect();
+
+    let mut preview = ImportPreview::default();
+    for row in incoming {
+        match existing.get(&key(row)) {
+            None => preview.added.push(row.state.clone()),
+            Some(current) => {
+                let fields = current.changed_fields(row);
+                if fields.is_empty() {
+                    preview.unchanged += 1;
+                } else {
+                    preview.changed.push(RowChange { state: current.state.clone(), fields });
+                }
+            }
+        }
+    }
+    preview.removed = current
+        .iter()
+        .filter(|row| !incoming_keys.contains(&key(row)))
+        .map(|row| row.state.clone())
+        .collect();
+    preview
+}
+
+fn format_formula(formula: &RateFormula) -> Result<String, String> {
+    match formula.base {
+        RateBase::Fixed { rate } if *formula == RateFormula::fixed(rate) => {
+            Ok(rate.normalize().to_string())
+        }
+        _ => serde_json::to_string(formula).map_err(|e| e.to_string()),
+    }
+}
+
+fn parse_formula(cell: &str) -> Result<RateFormula, String> {
+    let cell = cell.trim();
+    match Decimal::from_str(cell) {
+        Ok(rate) => Ok(RateFormula::fixed(rate)),
+        Err(_) => serde_json::from_str(cell).map_err(|e| e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RateIndex;
+    use rust_decimal_macros::dec;
+
+    fn rows() -> Vec<RateTableRow> {
+        let florida = StateRate {
+            citation: "Fla. Stat. § 55.03".to_string(),
+            effective_date: Some("2024-01-01".to_string()),
+            ..StateRate::new(
+                "Florida".to_string(),
+                RateFormula::fixed(dec!(4.75)),
+                UpdateFrequency::Annual,
+                "4.75% per annum, \"as of 2024\"".to_string(),
+            )
+        };
+        let period = StateRatePeriod {
+            id: 7,
+            state_id: 1,
+            effective_from: "2024-01-01".to_string(),
+            effective_to: None,
+            rate: dec!(9.09),
+            notes: String::new(),
+        };
+        let texas = StateRate::new(
+            "Texas".to_string(),
+            RateFormula::index(RateIndex::Prime, dec!(0)).with_floor(dec!(5)).with_cap(dec!(15)),
+            UpdateFrequency::Monthly,
+            String::new(),
+        );
+        vec![RateTableRow::new(&florida, &[period]), RateTableRow::new(&texas, &[])]
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for format in [TableFormat::Json, TableFormat::Csv] {
+            let exported = export_rows(&rows(), format).unwrap();
+            assert_eq!(parse_rows(&exported, format).unwrap(), rows());
+        }
+
+        let csv = export_rows(&rows(), TableFormat::Csv).unwrap();
+        assert!(csv.lines().nth(1).unwrap().starts_with("Florida,4.75,annual"));
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_row() {
+        let csv = "state,formula,update_frequency,last_update,notes,compounding,day_count,\
+                   rounding_stage,rounding_mode,rate_date_rule,rate_periods\n\
+                   Guam,6,Annual,2024-01-01,,none,actual_365,per_period,half_up,preceding_week,\n\
+                   Palau,six,Annual,2024-01-01,,none,actual_365,per_period,half_up,preceding_week,\n";
+        let error = parse_rows(csv, TableFormat::Csv).unwrap_err();
+        assert!(error.starts_with("Row 3"));
+
+        let mut duplicated = rows();
+        duplicated.push(RateTableRow { state: "TEXAS".to_string(), ..duplicated[1].clone() });
+        let json = export_rows(&duplicated, TableFormat::Json).unwrap();
+        assert!(parse_rows(&json, TableFormat::Json).is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let current = rows();
+        let mut incoming = rows();
+        incoming[0].formula = RateFormula::fixed(dec!(5));
+        incoming[0].rate_periods.clear();
+        incoming.remove(1);
+        incoming.push(RateTableRow { state: "Guam".to_string(), ..current[0].clone() });
+
+        let preview = diff_rows(&current, &incoming);
+        assert_eq!(preview.added, vec!["Guam"]);
+        assert_eq!(preview.removed, vec!["Texas"]);
+        assert_eq!(preview.changed.len(), 1);
+        assert_eq!(preview.changed[0].fields, vec!["formula", "rate_periods"]);
+        assert_eq!(preview.unchanged, 0);
+
+        let mut renamed = rows();
+        renamed[1].state = " TEXAS ".to_string();
+        let json = export_rows(&renamed, TableFormat::Json).unwrap();
+        let preview = diff_rows(&current, &parse_rows(&json, TableFormat::Json).unwrap());
+        assert_eq!(preview.changed.len(), 1);
+        assert_eq!(preview.changed[0].state, "Texas");
+        assert_eq!(preview.changed[0].fields, vec!["state"]);
+        assert_eq!(preview.unchanged, 1);
+    }
+}
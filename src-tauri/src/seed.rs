@@ -0,0 +1,610 @@
+use crate::db::{
+    create_state_rate, delete_state_rate, get_all_states, get_seed_baseline, get_state_rate_by_id,
+    get_state_rate_by_name, record_seed_baseline, replace_rate_periods, restore_state_rate,
+    state_in_audit, update_state_rate,
+};
+use crate::models::{
+    AuditContext, CompoundingMode, DayCount, RateDateRule, RateFormula, RoundingPolicy, StateRate,
+    UpdateFrequency,
+};
+use rusqlite::{Connection, Result};
+use serde::{Deserialize, Serialize};
+
+// The rate table the app ships. Bump `version` with every change to a value so
+// existing installs pick it up on their next start.
+const SEED_JSON: &str = include_str!("../seed/state_rates.json");
+
+// The tables earlier versions shipped, oldest first. Version 0 is the table
+// built into the app before seed tracking, as the formula migration left it.
+// Append the current table here before changing it.
+const PREVIOUS_SEED_JSON: &str = include_str!("../seed/previous_state_rates.json");
+
+#[derive(Debug, Deserialize)]
+pub struct SeedData {
+    pub version: i32,
+    pub state_rates: Vec<SeedRow>,
+    // Every row earlier versions shipped. A row from before seed tracking that
+    // still has one of these values is unedited.
+    #[serde(skip)]
+    pub previous: Vec<SeedRow>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SeedHistory {
+    tables: Vec<SeedData>,
+}
+
+// A jurisdiction as shipped: the fields a seed update may change. The row's
+// date, rate periods, verification and trash state belong to the install.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeedRow {
+    pub state: String,
+    pub formula: RateFormula,
+    pub update_frequency: UpdateFrequency,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub compounding: CompoundingMode,
+    #[serde(default)]
+    pub day_count: DayCount,
+    #[serde(default)]
+    pub rounding: RoundingPolicy,
+    #[serde(default)]
+    pub rate_date_rule: RateDateRule,
+    #[serde(default)]
+    pub citation: String,
+    #[serde(default)]
+    pub source_url: String,
+    #[serde(default)]
+    pub effective_date: Option<String>,
+}
+
+// The shipped values a local row was last brought in line with. Purging the row
+// unsets the row id.
+#[derive(Debug, Clone)]
+pub struct SeedBaseline {
+    pub state_rate_id: Option<i32>,
+    pub shipped: SeedRow,
+}
+
+// A shipped update to a row the user has edited, left for them to review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeedConflict {
+    pub local: StateRate,
+    pub shipped: SeedRow,
+    pub seed_version: i32,
+    // Names of the fields that differ
+    pub fields: Vec<String>,
+}
+
+// What a sync changed
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SeedReport {
+    pub seed_version: i32,
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub conflicts: Vec<SeedConflict>,
+}
+
+// What a reset changed
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ResetReport {
+    // Rows put back to their shipped values, recreated or taken out of the trash
+    pub reset: Vec<String>,
+    // Rows the app does not ship, moved to the trash by a full reset
+    pub trashed: Vec<String>,
+    // Where the table was exported before the reset
+    pub backup: String,
+}
+
+// How one shipped row relates to the local table
+enum SeedChange {
+    // Never shipped to this install and not in its table
+    Add(SeedRow),
+    // Unedited since it was last shipped; takes the new values
+    Update { local: StateRate, shipped: SeedRow },
+    // Already has the shipped values; only the baseline moves
+    Adopt { state_rate_id: i32, shipped: SeedRow },
+    Conflict(SeedConflict),
+}
+
+impl SeedRow {
+    pub fn new(state_rate: &StateRate) -> Self {
+        Self {
+            state: state_rate.state.clone(),
+            formula: state_rate.formula.clone(),
+            update_frequency: state_rate.update_frequency,
+            notes: state_rate.notes.clone(),
+            compounding: state_rate.compounding,
+            day_count: state_rate.day_count,
+            rounding: state_rate.rounding,
+            rate_date_rule: state_rate.rate_date_rule,
+            citation: state_rate.citation.clone(),
+            source_url: state_rate.source_url.clone(),
+            effective_date: state_rate.effective_date.clone(),
+        }
+    }
+
+    // The row as stored under `id`, dated today
+    pub fn to_state_rate(&self, id: i32) -> StateRate {
+        StateRate {
+            id,
+            compounding: self.compounding,
+            day_count: self.day_count,
+            rounding: self.rounding,
+            rate_date_rule: self.rate_date_rule,
+            citation: self.citation.clone(),
+            source_url: self.source_url.clone(),
+            effective_date: self.effective_date.clone(),
+            ..StateRate::new(
+                self.state.clone(),
+                self.formula.clone(),
+                self.update_frequency,
+                self.notes.clone(),
+            )
+        }
+    }
+
+    // `local` with each value the shipped row changed from `base`, the values the
+    // row was last shipped with. Everything else stays as the install has it; its
+    // date and verification only give way to a new formula.
+    fn apply_to(&self, local: &StateRate, base: &SeedRow) -> StateRate {
+        let mut merged = local.clone();
+        if self.state != base.state {
+            merged.state = self.state.clone();
+        }
+        if self.formula != base.formula {
+            merged.formula = self.formula.clone();
+        }
+        if self.update_frequency != base.update_frequency {
+            merged.update_frequency = self.update_frequency;
+        }
+        if self.notes != base.notes {
+            merged.notes = self.notes.clone();
+        }
+        if self.compounding != base.compounding {
+            merged.compounding = self.compounding;
+        }
+        if self.day_count != base.day_count {
+            merged.day_count = self.day_count;
+        }
+        if self.rounding != base.rounding {
+            merged.rounding = self.rounding;
+        }
+        if self.rate_date_rule != base.rate_date_rule {
+            merged.rate_date_rule = self.rate_date_rule;
+        }
+        if self.citation != base.citation {
+            merged.citation = self.citation.clone();
+        }
+        if self.source_url != base.source_url {
+            merged.source_url = self.source_url.clone();
+        }
+        if self.effective_date != base.effective_date {
+            merged.effective_date = self.effective_date.clone();
+        }
+        if merged.formula != local.formula {
+            merged.last_update = chrono::Local::now().format("%Y-%m-%d").to_string();
+            merged.verified_by.clear();
+            merged.verified_on = None;
+        }
+        merged
+    }
+
+    fn changed_fields(&self, other: &SeedRow) -> Vec<String> {
+        let fields = [
+            ("state", self.state != other.state),
+            ("formula", self.formula != other.formula),
+            ("update_frequency", self.update_frequency != other.update_frequency),
+            ("notes", self.notes != other.notes),
+            ("compounding", self.compounding != other.compounding),
+            ("day_count", self.day_count != other.day_count),
+            ("rounding", self.rounding != other.rounding),
+            ("rate_date_rule", self.rate_date_rule != other.rate_date_rule),
+            ("citation", self.citation != other.citation),
+            ("source_url", self.source_url != other.source_url),
+            ("effective_date", self.effective_date != other.effective_date),
+        ];
+        fields
+            .iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+}
+
+pub fn shipped() -> SeedData {
+    let mut seed: SeedData =
+        serde_json::from_str(SEED_JSON).expect("seed/state_rates.json is a valid seed file");
+    let history: SeedHistory = serde_json::from_str(PREVIOUS_SEED_JSON)
+        .expect("seed/previous_state_rates.json is a valid seed history");
+    seed.previous = history.tables.into_iter().flat_map(|table| table.state_rates).collect();
+    seed
+}
+
+// Compares each shipped row with its local counterpart. Rows the user trashed
+// or purged are left alone. A row with no baseline predates seed tracking or was
+// added by the user under a shipped name; it is adopted when it already has the
+// shipped values, updated when it has values an earlier version shipped, and
+// reported as a conflict otherwise.
+fn plan(conn: &Connection, seed: &SeedData) -> Result<Vec<SeedChange>> {
+    let mut changes = Vec::new();
+    for shipped in &seed.state_rates {
+        let (local, baseline) = match get_seed_baseline(conn, &shipped.state)? {
+            Some(baseline) if baseline.shipped == *shipped => continue,
+            Some(baseline) => match baseline.state_rate_id {
+                Some(id) => (get_state_rate_by_id(conn, id)?, Some(baseline.shipped)),
+                None => continue,
+            },
+            None => match get_state_rate_by_name(conn, &shipped.state)? {
+                Some(local) => (Some(local), None),
+                None if state_in_audit(conn, &shipped.state)? => continue,
+                None => {
+                    changes.push(SeedChange::Add(shipped.clone()));
+                    continue;
+                }
+            },
+        };
+        let Some(local) = local.filter(|local| local.deleted_at.is_none()) else {
+            continue;
+        };
+
+        let current = SeedRow::new(&local);
+        let change = if current == *shipped {
+            SeedChange::Adopt { state_rate_id: local.id, shipped: shipped.clone() }
+        } else if baseline.as_ref() == Some(&current)
+            || (baseline.is_none() && seed.previous.contains(&current))
+        {
+            SeedChange::Update { local, shipped: shipped.clone() }
+        } else {
+            SeedChange::Conflict(SeedConflict {
+                fields: current.changed_fields(shipped),
+                local,
+                shipped: shipped.clone(),
+                seed_version: seed.version,
+            })
+        };
+        changes.push(change);
+    }
+    Ok(changes)
+}
+
+// Applies the shipped rows the user has not edited. Callers run it in a
+// transaction.
+pub fn sync(conn: &Connection, seed: &SeedData) -> Result<SeedReport> {
+    let context = AuditContext {
+        changed_by: format!("Seed data v{}", seed.version),
+        reason: Some("Shipped rate table update".to_string()),
+    };
+    let mut report = SeedReport { seed_version: seed.version, ..Default::default() };
+    for change in plan(conn, seed)? {
+        match change {
+            SeedChange::Add(shipped) => {
+                let created = create_state_rate(conn, &shipped.to_state_rate(0), &context)?;
+                record_seed_baseline(conn, Some(created.id), seed.version, &shipped)?;
+                report.added.push(shipped.state);
+            }
+            SeedChange::Update { local, shipped } => {
+                let updated = shipped.apply_to(&local, &SeedRow::new(&local));
+                update_state_rate(conn, &updated, &context)?;
+                record_seed_baseline(conn, Some(local.id), seed.version, &shipped)?;
+                report.updated.push(shipped.state);
+            }
+            SeedChange::Adopt { state_rate_id, shipped } => {
+                record_seed_baseline(conn, Some(state_rate_id), seed.version, &shipped)?;
+            }
+            SeedChange::Conflict(conflict) => report.conflicts.push(conflict),
+        }
+    }
+    Ok(report)
+}
+
+pub fn conflicts(conn: &Connection, seed: &SeedData) -> Result<Vec<SeedConflict>> {
+    let conflicts = plan(conn, seed)?.into_iter().filter_map(|change| match change {
+        SeedChange::Conflict(conflict) => Some(conflict),
+        _ => None,
+    });
+    Ok(conflicts.collect())
+}
+
+// Settles the conflict over the shipped row named `state` by taking the shipped
+// values or keeping the local ones; either way it is not reported again until
+// the shipped values change. Returns false when there is no such conflict.
+pub fn resolve_conflict(
+    conn: &Connection,
+    seed: &SeedData,
+    state: &str,
+    accept_shipped: bool,
+    context: &AuditContext,
+) -> Result<bool> {
+    let Some(conflict) =
+        conflicts(conn, seed)?.into_iter().find(|conflict| conflict.shipped.state == state)
+    else {
+        return Ok(false);
+    };
+    if accept_shipped {
+        // Local edits to values the shipped row left alone survive taking it
+        let base = match get_seed_baseline(conn, &conflict.shipped.state)? {
+            Some(baseline) => baseline.shipped,
+            None => SeedRow::new(&conflict.local),
+        };
+        update_state_rate(conn, &conflict.shipped.apply_to(&conflict.local, &base), context)?;
+    }
+    record_seed_baseline(conn, Some(conflict.local.id), seed.version, &conflict.shipped)?;
+    Ok(true)
+}
+
+// Puts the named shipped rows, or the whole table when `states` is None, back to
+// the shipped values, clearing their rate periods. A full reset also trashes
+// rows the app does not ship. Names are matched ignoring case; callers check
+// they are all shipped.
+pub fn reset(
+    conn: &Connection,
+    seed: &SeedData,
+    states: Option<&[String]>,
+    context: &AuditContext,
+) -> Result<ResetReport> {
+    let selected = |state: &str| {
+        states.is_none_or(|states| states.iter().any(|name| name.eq_ignore_ascii_case(state)))
+    };
+    let mut report = ResetReport::default();
+    for shipped in seed.state_rates.iter().filter(|shipped| selected(&shipped.state)) {
+        let id = match get_state_rate_by_name(conn, &shipped.state)? {
+            Some(local) => {
+                if local.deleted_at.is_some() {
+                    restore_state_rate(conn, local.id, context)?;
+                }
+                update_state_rate(conn, &shipped.apply_to(&local, &SeedRow::new(&local)), context)?;
+                replace_rate_periods(conn, local.id, &[], context)?;
+                local.id
+            }
+            None => create_state_rate(conn, &shipped.to_state_rate(0), context)?.id,
+        };
+        record_seed_baseline(conn, Some(id), seed.version, shipped)?;
+        report.reset.push(shipped.state.clone());
+    }
+
+    if states.is_none() {
+        for local in get_all_states(conn)? {
+            let shipped = &seed.state_rates;
+            if !shipped.iter().any(|row| row.state.eq_ignore_ascii_case(&local.state)) {
+                delete_state_rate(conn, local.id, context)?;
+                report.trashed.push(local.state);
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{delete_state_rate, get_all_states, migrate};
+    use rust_decimal_macros::dec;
+
+    fn seeded(seed: &SeedData) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, None).unwrap();
+        sync(&conn, seed).unwrap();
+        conn
+    }
+
+    fn edit(conn: &Connection, state: &str, edit: impl FnOnce(&mut StateRate)) {
+        let mut row = get_state_rate_by_name(conn, state).unwrap().unwrap();
+        edit(&mut row);
+        let context = AuditContext { changed_by: "test".to_string(), reason: None };
+        update_state_rate(conn, &row, &context).unwrap();
+    }
+
+    #[test]
+    fn test_shipped_seed_parses() {
+        let seed = shipped();
+        assert!(seed.state_rates.iter().any(|row| row.state == "Federal"));
+        for row in &seed.state_rates {
+            assert_eq!(row.formula.validate(), Ok(()), "{}", row.state);
+        }
+    }
+
+    #[test]
+    fn test_sync_updates_only_unedited_rows() {
+        let mut seed = shipped();
+        let conn = seeded(&seed);
+        assert_eq!(get_all_states(&conn).unwrap().len(), seed.state_rates.len());
+
+        edit(&conn, "Alabama", |row| row.notes = "Checked by counsel".to_string());
+        let context = AuditContext { changed_by: "test".to_string(), reason: None };
+        let arizona = get_state_rate_by_name(&conn, "Arizona").unwrap().unwrap();
+        delete_state_rate(&conn, arizona.id, &context).unwrap();
+
+        seed.version += 1;
+        for row in seed.state_rates.iter_mut() {
+            if ["Alabama", "Alaska", "Arizona"].contains(&row.state.as_str()) {
+                row.formula = RateFormula::fixed(dec!(6.5));
+            }
+        }
+        seed.state_rates.push(SeedRow::new(&StateRate::new(
+            "Guam".to_string(),
+            RateFormula::fixed(dec!(6)),
+            UpdateFrequency::Annual,
+            String::new(),
+        )));
+
+        let report = sync(&conn, &seed).unwrap();
+        assert_eq!(report.added, vec!["Guam"]);
+        assert_eq!(report.updated, vec!["Alaska"]);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].local.state, "Alabama");
+        assert_eq!(report.conflicts[0].fields, vec!["formula", "notes"]);
+
+        let alaska = get_state_rate_by_name(&conn, "Alaska").unwrap().unwrap();
+        assert_eq!(alaska.formula, RateFormula::fixed(dec!(6.5)));
+        let arizona = get_state_rate_by_name(&conn, "Arizona").unwrap().unwrap();
+        assert_eq!(arizona.formula, RateFormula::fixed(dec!(10.0)));
+
+        // A second start changes nothing and still reports the conflict
+        let report = sync(&conn, &seed).unwrap();
+        assert!(report.added.is_empty() && report.updated.is_empty());
+        assert_eq!(report.conflicts.len(), 1);
+
+        assert!(resolve_conflict(&conn, &seed, "Alabama", false, &context).unwrap());
+        assert!(conflicts(&conn, &seed).unwrap().is_empty());
+        let alabama = get_state_rate_by_name(&conn, "Alabama").unwrap().unwrap();
+        assert_eq!(alabama.notes, "Checked by counsel");
+    }
+
+    #[test]
+    fn test_shipped_changes_keep_local_stamps() {
+        let mut seed = shipped();
+        let conn = seeded(&seed);
+        let context = AuditContext { changed_by: "test".to_string(), reason: None };
+        let verify = |row: &mut StateRate| {
+            row.last_update = "2024-05-01".to_string();
+            row.verified_by = "clerk".to_string();
+            row.verified_on = Some("2024-05-01".to_string());
+        };
+        for state in ["Alabama", "Alaska", "Arizona"] {
+            edit(&conn, state, verify);
+        }
+        edit(&conn, "Alabama", |row| row.citation = "Ala. Code § 8-8-10".to_string());
+
+        seed.version += 1;
+        for row in seed.state_rates.iter_mut() {
+            match row.state.as_str() {
+                "Alabama" => row.notes = "Revised".to_string(),
+                "Alaska" => row.formula = RateFormula::fixed(dec!(6.5)),
+                "Arizona" => row.source_url = "https://www.azleg.gov/".to_string(),
+                _ => {}
+            }
+        }
+        let report = sync(&conn, &seed).unwrap();
+        assert_eq!(report.updated, vec!["Alaska", "Arizona"]);
+        assert!(resolve_conflict(&conn, &seed, "Alabama", true, &context).unwrap());
+
+        // A new formula needs checking again
+        let alaska = get_state_rate_by_name(&conn, "Alaska").unwrap().unwrap();
+        assert_eq!(alaska.formula, RateFormula::fixed(dec!(6.5)));
+        assert_eq!(alaska.verified_on, None);
+        assert_ne!(alaska.last_update, "2024-05-01");
+
+        let arizona = get_state_rate_by_name(&conn, "Arizona").unwrap().unwrap();
+        assert_eq!(arizona.source_url, "https://www.azleg.gov/");
+        assert_eq!(arizona.verified_by, "clerk");
+        assert_eq!(arizona.last_update, "2024-05-01");
+
+        let alabama = get_state_rate_by_name(&conn, "Alabama").unwrap().unwrap();
+        assert_eq!(alabama.notes, "Revised");
+        assert_eq!(alabama.citation, "Ala. Code § 8-8-10");
+        assert_eq!(alabama.verified_on.as_deref(), Some("2024-05-01"));
+
+        let states = vec!["Alabama".to_string()];
+        reset(&conn, &seed, Some(&states), &context).unwrap();
+        let alabama = get_state_rate_by_name(&conn, "Alabama").unwrap().unwrap();
+        let shipped = seed.state_rates.iter().find(|row| row.state == "Alabama").unwrap();
+        assert_eq!(SeedRow::new(&alabama), *shipped);
+        assert_eq!(alabama.verified_by, "clerk");
+    }
+
+    #[test]
+    fn test_reset() {
+        let seed = shipped();
+        let conn = seeded(&seed);
+        let context = AuditContext { changed_by: "test".to_string(), reason: None };
+        edit(&conn, "Alabama", |row| row.formula = RateFormula::fixed(dec!(1)));
+        edit(&conn, "Alaska", |row| row.formula = RateFormula::fixed(dec!(2)));
+        let arizona = get_state_rate_by_name(&conn, "Arizona").unwrap().unwrap();
+        delete_state_rate(&conn, arizona.id, &context).unwrap();
+        let guam = StateRate::new(
+            "Guam".to_string(),
+            RateFormula::fixed(dec!(6)),
+            UpdateFrequency::Annual,
+            String::new(),
+        );
+        create_state_rate(&conn, &guam, &context).unwrap();
+
+        let states = vec!["alabama".to_string()];
+        let report = reset(&conn, &seed, Some(&states), &context).unwrap();
+        assert_eq!(report.reset, vec!["Alabama"]);
+        let alaska = get_state_rate_by_name(&conn, "Alaska").unwrap().unwrap();
+        assert_eq!(alaska.formula, RateFormula::fixed(dec!(2)));
+
+        let report = reset(&conn, &seed, None, &context).unwrap();
+        assert_eq!(report.reset.len(), seed.state_rates.len());
+        assert_eq!(report.trashed, vec!["Guam"]);
+        let local: Vec<SeedRow> = get_all_states(&conn).unwrap().iter().map(SeedRow::new).collect();
+        assert_eq!(local.len(), seed.state_rates.len());
+        assert!(seed.state_rates.iter().all(|shipped| local.contains(shipped)));
+        assert!(conflicts(&conn, &seed).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_shipped_history_parses() {
+        let seed = shipped();
+        assert!(seed.previous.len() > seed.state_rates.len());
+        for row in &seed.previous {
+            assert_eq!(row.formula.validate(), Ok(()), "{}", row.state);
+        }
+    }
+
+    // An install made before seed tracking, with rows as the built-in table and
+    // the formula migration left them
+    #[test]
+    fn test_upgrade_from_untracked_install() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE state_rates (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                state TEXT NOT NULL UNIQUE,
+                rate REAL NOT NULL,
+                is_variable INTEGER NOT NULL,
+                plus_percentage REAL NOT NULL,
+                update_frequency TEXT NOT NULL,
+                last_update TEXT NOT NULL,
+                notes TEXT
+            );
+            INSERT INTO state_rates
+                (state, rate, is_variable, plus_percentage, update_frequency, last_update, notes)
+            VALUES
+                ('Federal', 0.0, 1, 0.0, 'Weekly', '2024-01-01', 'Based on 1-year Treasury yield'),
+                ('Alabama', 7.0, 0, 0.0, 'Annual', '2024-01-01', '7% per annum'),
+                ('Florida', 4.75, 0, 0.0, 'Annual', '2024-01-01', '4.75% per annum as of 2024'),
+                ('Nevada', 5.25, 0, 0.0, 'Annual', '2024-01-01', 'Prime rate + 2%'),
+                ('Texas', 5.0, 0, 0.0, 'Annual', '2024-01-01',
+                 '5% per annum if parties agree, 18% otherwise');",
+        )
+        .unwrap();
+        migrate(&conn, None).unwrap();
+
+        let seed = shipped();
+        let report = sync(&conn, &seed).unwrap();
+        assert_eq!(report.updated, vec!["Federal", "Nevada", "Texas"]);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].local.state, "Alabama");
+        assert_eq!(report.added.len(), seed.state_rates.len() - 5);
+
+        let local: Vec<SeedRow> = get_all_states(&conn).unwrap().iter().map(SeedRow::new).collect();
+        for state in ["Federal", "Florida", "Nevada", "Texas"] {
+            let shipped = seed.state_rates.iter().find(|row| row.state == state).unwrap();
+            assert!(local.contains(shipped), "{}", state);
+            assert!(get_seed_baseline(&conn, state).unwrap().is_some(), "{}", state);
+        }
+        assert!(get_seed_baseline(&conn, "Alabama").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rows_without_baseline() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn, None).unwrap();
+        let seed = shipped();
+        let mut florida =
+            seed.state_rates.iter().find(|row| row.state == "Florida").unwrap().clone();
+        crate::db::insert_state_rate(&conn, &florida.to_state_rate(0)).unwrap();
+        florida.state = "Georgia".to_string();
+        crate::db::insert_state_rate(&conn, &florida.to_state_rate(0)).unwrap();
+
+        let report = sync(&conn, &seed).unwrap();
+        assert!(!report.added.contains(&"Florida".to_string()));
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].shipped.state, "Georgia");
+        assert!(get_seed_baseline(&conn, "Florida").unwrap().is_some());
+    }
+}
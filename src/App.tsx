@@ -1,18 +1,37 @@
 import { useState, useEffect } from 'react';
 import CalculatorTab from './components/CalculatorTab';
 import DbManagerTab from './components/DbManagerTab';
+import MattersTab from './components/MattersTab';
 import SettingsTab from './components/SettingsTab';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { LINT_EVENT, LintReport, SavedCalculation } from './types';
 
 function App() {
-  const [activeTab, setActiveTab] = useState<'calculator' | 'database' | 'settings'>('calculator');
+  const [activeTab, setActiveTab] = useState<'calculator' | 'matters' | 'database' | 'settings'>('calculator');
+  const [openedCalculation, setOpenedCalculation] = useState<SavedCalculation | null>(null);
   const [apiKeyConfigured, setApiKeyConfigured] = useState(false);
+  const [lintReport, setLintReport] = useState<LintReport | null>(null);
 
   useEffect(() => {
     // Check if API key is configured
     checkApiKey();
   }, []);
 
+  // The startup check may be sent before this listener exists, so the table is
+  // also checked once on mount
+  useEffect(() => {
+    const unlisten = listen<LintReport>(LINT_EVENT, event => setLintReport(event.payload));
+    invoke<LintReport>('lint_rate_table_command')
+      .then(setLintReport)
+      .catch(error => console.error('Failed to check the rate table:', error));
+    return () => {
+      unlisten.then(stop => stop());
+    };
+  }, []);
+
+  const inconsistentRows = new Set(lintReport?.issues.map(issue => issue.state_rate_id)).size;
+
   const checkApiKey = async () => {
     try {
       const configured = await invoke<boolean>('get_api_key_configured');
@@ -22,6 +41,12 @@ function App() {
     }
   };
 
+  // Opens a saved calculation in the calculator
+  const handleOpenCalculation = (calculation: SavedCalculation) => {
+    setOpenedCalculation(calculation);
+    setActiveTab('calculator');
+  };
+
   const handleApiKeySet = () => {
     setApiKeyConfigured(true);
     setActiveTab('calculator');
@@ -42,6 +67,21 @@ function App() {
           </div>
         )}
 
+        {inconsistentRows > 0 && activeTab !== 'database' && (
+          <div className="mb-6 p-4 bg-orange-50 border border-orange-300 rounded-lg flex justify-between items-center">
+            <p className="text-sm text-orange-800">
+              {inconsistentRows} {inconsistentRows === 1 ? 'rate table row has' : 'rate table rows have'} fields
+              that contradict each other. Calculations using them may be wrong.
+            </p>
+            <button
+              onClick={() => setActiveTab('database')}
+              className="px-3 py-1 text-sm border border-orange-400 rounded-md hover:bg-orange-100"
+            >
+              Review
+            </button>
+          </div>
+        )}
+
         <div className="bg-white rounded-lg shadow-md">
           <div className="border-b border-gray-200">
             <nav className="flex">
@@ -55,6 +95,16 @@ function App() {
               >
                 Calculator
               </button>
+              <button
+                className={`px-6 py-3 text-sm font-medium ${
+                  activeTab === 'matters'
+                    ? 'text-blue-600 border-b-2 border-blue-600'
+                    : 'text-gray-500 hover:text-gray-700'
+                }`}
+                onClick={() => setActiveTab('matters')}
+              >
+                Matters
+              </button>
               <button
                 className={`px-6 py-3 text-sm font-medium ${
                   activeTab === 'database'
@@ -79,7 +129,8 @@ function App() {
           </div>
 
           <div className="p-6">
-            {activeTab === 'calculator' && <CalculatorTab />}
+            {activeTab === 'calculator' && <CalculatorTab opened={openedCalculation} />}
+            {activeTab === 'matters' && <MattersTab onOpen={handleOpenCalculation} />}
             {activeTab === 'database' && <DbManagerTab />}
             {activeTab === 'settings' && (
               <SettingsTab onApiKeySet={handleApiKeySet} />
@@ -2,27 +2,83 @@ import React, { useState, useEffect } from 'react';
 import DatePicker from 'react-datepicker';
 import 'react-datepicker/dist/react-datepicker.css';
 import { invoke } from '@tauri-apps/api/core';
-import { CalcRequest, CalcResponse, StateRate } from '../types';
+import { toAppError } from '../errors';
+import {
+  AppError,
+  CalcRequest,
+  CalcResponse,
+  DayCount,
+  Decimal,
+  Matter,
+  PaymentAllocation,
+  SavedCalculation,
+  ScheduleInterval,
+  StateRate,
+  WarningSeverity,
+} from '../types';
 
-const CalculatorTab: React.FC = () => {
+const WARNING_STYLES: Record<WarningSeverity, string> = {
+  critical: 'bg-red-50 border-red-300 text-red-800',
+  warning: 'bg-yellow-50 border-yellow-300 text-yellow-800',
+  info: 'bg-blue-50 border-blue-200 text-blue-800',
+};
+
+interface CalculatorTabProps {
+  // A saved calculation to show, opened from the Matters tab
+  opened?: SavedCalculation | null;
+}
+
+const CalculatorTab: React.FC<CalculatorTabProps> = ({ opened }) => {
   const [formData, setFormData] = useState<CalcRequest>({
     judgment_date: new Date().toISOString().split('T')[0],
     is_federal: true,
     state: '',
-    amount: 0,
+    amount: '0',
     from_date: new Date().toISOString().split('T')[0],
     to_date: new Date().toISOString().split('T')[0],
+    day_count: null,
+    payments: [],
+    payment_allocation: 'us_rule',
+    schedule_interval: 'monthly',
   });
 
   const [states, setStates] = useState<StateRate[]>([]);
   const [result, setResult] = useState<CalcResponse | null>(null);
-  const [error, setError] = useState<string>('');
+  const [error, setError] = useState<AppError | null>(null);
   const [loading, setLoading] = useState(false);
+  // The request that produced `result`, which is what gets saved
+  const [calculatedRequest, setCalculatedRequest] = useState<CalcRequest | null>(null);
+  const [matters, setMatters] = useState<Matter[]>([]);
+  const [saveMatterId, setSaveMatterId] = useState<number | null>(null);
+  const [saveName, setSaveName] = useState('');
+  const [saveNotice, setSaveNotice] = useState('');
 
   useEffect(() => {
     loadStates();
+    loadMatters();
   }, []);
 
+  useEffect(() => {
+    if (opened) {
+      setFormData(opened.request);
+      setCalculatedRequest(opened.request);
+      setResult(opened.response);
+      setSaveMatterId(opened.matter_id);
+      setSaveName(opened.name);
+      setSaveNotice(`Opened "${opened.name}", saved ${opened.calculated_at}`);
+    }
+  }, [opened]);
+
+  const loadMatters = async () => {
+    try {
+      const saved = await invoke<Matter[]>('get_matters_command');
+      setMatters(saved);
+      setSaveMatterId(prev => prev ?? saved[0]?.id ?? null);
+    } catch (err) {
+      console.error('Failed to load matters:', err);
+    }
+  };
+
   const loadStates = async () => {
     try {
       const stateRates = await invoke<StateRate[]>('get_all_state_rates');
@@ -38,8 +94,9 @@ const CalculatorTab: React.FC = () => {
 
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
-    setError('');
+    setError(null);
     setResult(null);
+    setSaveNotice('');
     setLoading(true);
 
     try {
@@ -47,14 +104,38 @@ const CalculatorTab: React.FC = () => {
         request: formData,
       });
       setResult(response);
+      setCalculatedRequest(formData);
     } catch (err) {
-      setError(err as string);
+      setError(toAppError(err));
     } finally {
       setLoading(false);
     }
   };
 
-  const handleDateChange = (field: 'judgment_date' | 'from_date' | 'to_date', date: Date | null) => {
+  const handleSaveCalculation = async () => {
+    if (!result || !calculatedRequest || saveMatterId === null) {
+      return;
+    }
+    setError(null);
+    try {
+      await invoke('save_calculation_command', {
+        matterId: saveMatterId,
+        name: saveName || `Through ${calculatedRequest.to_date}`,
+        request: calculatedRequest,
+        response: result,
+      });
+      const matter = matters.find(m => m.id === saveMatterId);
+      setSaveNotice(`Saved to ${matter?.caption ?? 'the matter'}`);
+      await loadMatters();
+    } catch (err) {
+      setError(toAppError(err));
+    }
+  };
+
+  const handleDateChange = (
+    field: 'judgment_date' | 'verdict_date' | 'from_date' | 'to_date',
+    date: Date | null,
+  ) => {
     if (date) {
       setFormData(prev => ({
         ...prev,
@@ -63,11 +144,39 @@ const CalculatorTab: React.FC = () => {
     }
   };
 
-  const formatCurrency = (amount: number) => {
+  const addPayment = () => {
+    setFormData(prev => ({
+      ...prev,
+      payments: [...prev.payments, { date: prev.to_date, amount: '0' }],
+    }));
+  };
+
+  const updatePayment = (index: number, field: 'date' | 'amount', value: string) => {
+    setFormData(prev => ({
+      ...prev,
+      payments: prev.payments.map((payment, i) => i !== index ? payment : {
+        ...payment,
+        [field]: field === 'amount' ? value || '0' : value,
+      }),
+    }));
+  };
+
+  const removePayment = (index: number) => {
+    setFormData(prev => ({
+      ...prev,
+      payments: prev.payments.filter((_, i) => i !== index),
+    }));
+  };
+
+  // Some states fix the rate on the verdict date rather than the judgment date
+  const needsVerdictDate = !formData.is_federal
+    && states.find(s => s.state === formData.state)?.rate_date_rule === 'verdict_date';
+
+  const formatCurrency = (amount: Decimal) => {
     return new Intl.NumberFormat('en-US', {
       style: 'currency',
       currency: 'USD',
-    }).format(amount);
+    }).format(Number(amount));
   };
 
   return (
@@ -124,6 +233,20 @@ const CalculatorTab: React.FC = () => {
             />
           </div>
 
+          {needsVerdictDate && (
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">
+                Verdict Date
+              </label>
+              <DatePicker
+                selected={formData.verdict_date ? new Date(formData.verdict_date) : null}
+                onChange={(date) => handleDateChange('verdict_date', date)}
+                dateFormat="yyyy-MM-dd"
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              />
+            </div>
+          )}
+
           <div>
             <label className="block text-sm font-medium text-gray-700 mb-1">
               Interest Start Date
@@ -159,10 +282,10 @@ const CalculatorTab: React.FC = () => {
             </span>
             <input
               type="number"
-              value={formData.amount || ''}
+              value={Number(formData.amount) ? formData.amount : ''}
               onChange={(e) => setFormData(prev => ({ 
                 ...prev, 
-                amount: parseFloat(e.target.value) || 0 
+                amount: e.target.value || '0' 
               }))}
               step="0.01"
               min="0"
@@ -172,6 +295,80 @@ const CalculatorTab: React.FC = () => {
           </div>
         </div>
 
+        <div>
+          <label className="block text-sm font-medium text-gray-700 mb-1">
+            Day Count
+          </label>
+          <select
+            value={formData.day_count ?? ''}
+            onChange={(e) => setFormData(prev => ({
+              ...prev,
+              day_count: e.target.value ? e.target.value as DayCount : null,
+            }))}
+            className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+          >
+            <option value="">Jurisdiction default</option>
+            <option value="actual_365">Actual/365</option>
+            <option value="actual_actual">Actual/Actual</option>
+            <option value="actual_360">Actual/360</option>
+            <option value="thirty_360">30/360</option>
+          </select>
+        </div>
+
+        <div>
+          <div className="flex items-center justify-between mb-1">
+            <label className="block text-sm font-medium text-gray-700">
+              Payments
+            </label>
+            <div className="flex items-center gap-2">
+              <select
+                value={formData.payment_allocation}
+                onChange={(e) => setFormData(prev => ({
+                  ...prev,
+                  payment_allocation: e.target.value as PaymentAllocation,
+                }))}
+                className="px-2 py-1 text-sm border border-gray-300 rounded-md"
+              >
+                <option value="us_rule">Interest first (U.S. Rule)</option>
+                <option value="pro_rata">Pro rata</option>
+              </select>
+              <button
+                type="button"
+                onClick={addPayment}
+                className="px-2 py-1 text-sm border border-gray-300 rounded-md hover:bg-gray-50"
+              >
+                Add Payment
+              </button>
+            </div>
+          </div>
+          {formData.payments.map((payment, index) => (
+            <div key={index} className="flex gap-2 mb-2">
+              <input
+                type="date"
+                value={payment.date}
+                onChange={(e) => updatePayment(index, 'date', e.target.value)}
+                className="px-3 py-2 border border-gray-300 rounded-md"
+              />
+              <input
+                type="number"
+                value={Number(payment.amount) ? payment.amount : ''}
+                onChange={(e) => updatePayment(index, 'amount', e.target.value)}
+                step="0.01"
+                min="0"
+                placeholder="0.00"
+                className="flex-1 px-3 py-2 border border-gray-300 rounded-md"
+              />
+              <button
+                type="button"
+                onClick={() => removePayment(index)}
+                className="px-2 py-1 text-xs bg-red-500 text-white rounded hover:bg-red-600"
+              >
+                Remove
+              </button>
+            </div>
+          ))}
+        </div>
+
         <div className="pt-4">
           <button
             type="submit"
@@ -185,29 +382,65 @@ const CalculatorTab: React.FC = () => {
 
       {error && (
         <div className="p-4 bg-red-100 border border-red-400 rounded-lg">
-          <p className="text-red-700">{error}</p>
+          <p className="text-red-700">{error.message}</p>
+          {error.code === 'api_key_missing' && (
+            <p className="text-sm text-red-700">Add a FRED API key on the Settings tab.</p>
+          )}
+          {error.details && <p className="text-xs text-red-600 mt-1">{error.details}</p>}
         </div>
       )}
 
       {result && (
         <div className="bg-gray-50 p-6 rounded-lg space-y-4">
           <h3 className="text-lg font-semibold text-gray-800">Calculation Result</h3>
+
+          {result.warnings.length > 0 && (
+            <ul className="space-y-2">
+              {result.warnings.map((warning, index) => (
+                <li key={index} className={`p-3 border rounded-md text-sm ${WARNING_STYLES[warning.severity]}`}>
+                  <span className="font-medium uppercase text-xs mr-2">{warning.severity}</span>
+                  {warning.message}
+                </li>
+              ))}
+            </ul>
+          )}
           
           <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
             <div>
               <p className="text-sm text-gray-500">Interest Rate</p>
-              <p className="text-lg font-medium">{result.rate.toFixed(2)}%</p>
-              <p className="text-xs text-gray-500">{result.rate_source}</p>
+              <p className="text-lg font-medium">{Number(result.rate).toFixed(2)}%</p>
+              <p className="text-xs text-gray-500">{result.rate_source.description}</p>
+              {result.rate_source.citation && (
+                <p className="text-xs text-gray-500">
+                  {result.rate_source.source_url ? (
+                    <a href={result.rate_source.source_url} className="underline hover:text-blue-800" target="_blank" rel="noopener noreferrer">
+                      {result.rate_source.citation}
+                    </a>
+                  ) : result.rate_source.citation}
+                  {result.rate_source.effective_date && ` (effective ${result.rate_source.effective_date})`}
+                </p>
+              )}
+              {result.rate_source.verification_note && (
+                <p className="text-xs text-gray-500">Verified: {result.rate_source.verification_note}</p>
+              )}
+              {result.rate_window && (
+                <p className="text-xs text-gray-500">{result.rate_window}</p>
+              )}
             </div>
             
             <div>
               <p className="text-sm text-gray-500">Days</p>
               <p className="text-lg font-medium">{result.days}</p>
+              <p className="text-xs text-gray-500">Day count: {result.day_count}</p>
+              <p className="text-xs text-gray-500">
+                Rounding: {result.rounding.stage}, {result.rounding.mode}
+              </p>
             </div>
             
             <div>
               <p className="text-sm text-gray-500">Interest Amount</p>
               <p className="text-lg font-medium">{formatCurrency(result.interest_amount)}</p>
+              <p className="text-xs text-gray-500">Per diem: {formatCurrency(result.per_diem)}</p>
             </div>
             
             <div>
@@ -218,11 +451,190 @@ const CalculatorTab: React.FC = () => {
             </div>
           </div>
 
+          {result.rate_steps.length > 1 && (
+            <div className="pt-4 border-t border-gray-200">
+              <p className="text-sm text-gray-500 mb-2">Rate Determination</p>
+              <table className="w-full text-sm">
+                <tbody>
+                  {result.rate_steps.map((step, index) => (
+                    <tr key={index}>
+                      <td className="py-1">{step.description}</td>
+                      <td className="py-1 text-right">{Number(step.rate).toFixed(3)}%</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            </div>
+          )}
+
+          {result.rate_segments.length > 1 && (
+            <div className="pt-4 border-t border-gray-200">
+              <p className="text-sm text-gray-500 mb-2">Rate Periods</p>
+              <table className="w-full text-sm">
+                <thead>
+                  <tr className="text-left text-gray-500">
+                    <th className="py-1">Period</th>
+                    <th className="py-1">Days</th>
+                    <th className="py-1">Rate</th>
+                    <th className="py-1">Interest</th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {result.rate_segments.map(segment => (
+                    <tr key={segment.start_date}>
+                      <td className="py-1">{segment.start_date} – {segment.end_date}</td>
+                      <td className="py-1">{segment.days}</td>
+                      <td className="py-1">{Number(segment.rate).toFixed(2)}%</td>
+                      <td className="py-1">{formatCurrency(segment.interest)}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            </div>
+          )}
+
+          {result.periods.length > 1 && (
+            <div className="pt-4 border-t border-gray-200">
+              <p className="text-sm text-gray-500 mb-2">Compounding Periods</p>
+              <table className="w-full text-sm">
+                <thead>
+                  <tr className="text-left text-gray-500">
+                    <th className="py-1">Period</th>
+                    <th className="py-1">Days</th>
+                    <th className="py-1">Opening Balance</th>
+                    <th className="py-1">Interest</th>
+                    <th className="py-1">Closing Balance</th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {result.periods.map(period => (
+                    <tr key={period.start_date}>
+                      <td className="py-1">{period.start_date} – {period.end_date}</td>
+                      <td className="py-1">{period.days}</td>
+                      <td className="py-1">{formatCurrency(period.opening_balance)}</td>
+                      <td className="py-1">{formatCurrency(period.interest)}</td>
+                      <td className="py-1">{formatCurrency(period.closing_balance)}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            </div>
+          )}
+
+          {formData.payments.length > 0 && (
+            <div className="pt-4 border-t border-gray-200">
+              <p className="text-sm text-gray-500 mb-2">Payment Ledger</p>
+              <table className="w-full text-sm">
+                <thead>
+                  <tr className="text-left text-gray-500">
+                    <th className="py-1">Date</th>
+                    <th className="py-1">Interest Accrued</th>
+                    <th className="py-1">Payment</th>
+                    <th className="py-1">To Interest</th>
+                    <th className="py-1">To Principal</th>
+                    <th className="py-1">Balance</th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {result.ledger.map((row, index) => (
+                    <tr key={index}>
+                      <td className="py-1">{row.date}</td>
+                      <td className="py-1">{formatCurrency(row.interest_accrued)}</td>
+                      <td className="py-1">{formatCurrency(row.payment)}</td>
+                      <td className="py-1">{formatCurrency(row.applied_to_interest)}</td>
+                      <td className="py-1">{formatCurrency(row.applied_to_principal)}</td>
+                      <td className="py-1">{formatCurrency(row.balance)}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            </div>
+          )}
+
+          <div className="pt-4 border-t border-gray-200">
+            <div className="flex items-center justify-between mb-2">
+              <p className="text-sm text-gray-500">Accrual Schedule</p>
+              <select
+                value={formData.schedule_interval}
+                onChange={(e) => setFormData(prev => ({
+                  ...prev,
+                  schedule_interval: e.target.value as ScheduleInterval,
+                }))}
+                className="px-2 py-1 text-sm border border-gray-300 rounded-md"
+              >
+                <option value="monthly">Monthly</option>
+                <option value="daily">Daily</option>
+              </select>
+            </div>
+            <div className="max-h-80 overflow-y-auto">
+              <table className="w-full text-sm">
+                <thead>
+                  <tr className="text-left text-gray-500">
+                    <th className="py-1">Date</th>
+                    <th className="py-1">Days</th>
+                    <th className="py-1">Interest</th>
+                    <th className="py-1">Payments</th>
+                    <th className="py-1">Cumulative Interest</th>
+                    <th className="py-1">Balance</th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {result.schedule.map(row => (
+                    <tr key={row.date}>
+                      <td className="py-1">{row.date}</td>
+                      <td className="py-1">{row.days}</td>
+                      <td className="py-1">{formatCurrency(row.interest)}</td>
+                      <td className="py-1">{formatCurrency(row.payments)}</td>
+                      <td className="py-1">{formatCurrency(row.cumulative_interest)}</td>
+                      <td className="py-1">{formatCurrency(row.balance)}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            </div>
+          </div>
+
           <div className="pt-4 border-t border-gray-200">
             <p className="text-sm text-gray-600 italic">
               {result.disclaimer}
             </p>
           </div>
+
+          <div className="pt-4 border-t border-gray-200">
+            <h4 className="font-medium text-gray-700 mb-2">Save to Matter</h4>
+            {matters.length === 0 ? (
+              <p className="text-sm text-gray-500">Create a matter on the Matters tab to save calculations.</p>
+            ) : (
+              <div className="flex gap-2">
+                <select
+                  value={saveMatterId ?? ''}
+                  onChange={(e) => setSaveMatterId(Number(e.target.value))}
+                  className="flex-1 px-3 py-2 border border-gray-300 rounded-md"
+                >
+                  {matters.map(matter => (
+                    <option key={matter.id} value={matter.id}>
+                      {matter.caption}{matter.docket_number && ` (${matter.docket_number})`}
+                    </option>
+                  ))}
+                </select>
+                <input
+                  type="text"
+                  value={saveName}
+                  onChange={(e) => setSaveName(e.target.value)}
+                  placeholder={calculatedRequest ? `Through ${calculatedRequest.to_date}` : 'Name'}
+                  className="flex-1 px-3 py-2 border border-gray-300 rounded-md"
+                />
+                <button
+                  type="button"
+                  onClick={handleSaveCalculation}
+                  className="px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700"
+                >
+                  Save
+                </button>
+              </div>
+            )}
+            {saveNotice && <p className="text-sm text-green-700 mt-2">{saveNotice}</p>}
+          </div>
         </div>
       )}
     </div>
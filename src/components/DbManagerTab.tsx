@@ -1,85 +1,223 @@
 import React, { useState, useEffect, useMemo } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { StateRate } from '../types';
+import { listen } from '@tauri-apps/api/event';
+import {
+  CompoundingMode,
+  DayCount,
+  LINT_EVENT,
+  LintIssue,
+  LintReport,
+  RateDateRule,
+  RoundingMode,
+  RoundingStage,
+  AuditSnapshot,
+  ImportPreview,
+  ResetReport,
+  SeedConflict,
+  StateRate,
+  StateRateAudit,
+  StateRatePeriod,
+  TableFormat,
+  UpdateFrequency,
+} from '../types';
+import { describeError } from '../errors';
+import RateFormulaEditor, { describeFormula } from './RateFormulaEditor';
 import { AgGridReact } from 'ag-grid-react';
 import { ColDef } from 'ag-grid-community';
 import 'ag-grid-community/styles/ag-grid.css';
 import 'ag-grid-community/styles/ag-theme-alpine.css';
 
+const UPDATE_FREQUENCY_LABELS: Record<UpdateFrequency, string> = {
+  weekly: 'Weekly',
+  monthly: 'Monthly',
+  quarterly: 'Quarterly',
+  semi_annual: 'Semi-Annual',
+  annual: 'Annual',
+};
+
+const newStateRate = (): StateRate => ({
+  id: 0,
+  state: '',
+  formula: { base: { kind: 'fixed', rate: '0' }, rounding_increment: null, spread: '0', floor: null, cap: null },
+  update_frequency: 'annual',
+  last_update: new Date().toISOString().split('T')[0],
+  notes: '',
+  compounding: 'none',
+  day_count: 'actual_365',
+  rounding: { stage: 'per_period', mode: 'half_up' },
+  rate_date_rule: 'preceding_week',
+  deleted_at: null,
+  citation: '',
+  source_url: '',
+  effective_date: null,
+  verification_note: '',
+  verified_by: '',
+  verified_on: null,
+  next_review: null,
+});
+
+const today = () => new Date().toISOString().split('T')[0];
+
+// Unverified rows are due at once
+const isOverdue = (stateRate: StateRate) =>
+  !stateRate.next_review || stateRate.next_review < today();
+
 const DbManagerTab: React.FC = () => {
   const [stateRates, setStateRates] = useState<StateRate[]>([]);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string>('');
   const [editingRate, setEditingRate] = useState<StateRate | null>(null);
+  const [historyRate, setHistoryRate] = useState<StateRate | null>(null);
+  const [showTrash, setShowTrash] = useState(false);
+  const [importFile, setImportFile] = useState<{ format: TableFormat; contents: string } | null>(null);
+  const [seedConflicts, setSeedConflicts] = useState<SeedConflict[]>([]);
+  const [showSeedConflicts, setShowSeedConflicts] = useState(false);
+  const [selectedStates, setSelectedStates] = useState<string[]>([]);
+  const [notice, setNotice] = useState('');
+  const [overdueCount, setOverdueCount] = useState(0);
+  const [showOverdue, setShowOverdue] = useState(false);
+  const [lintIssues, setLintIssues] = useState<LintIssue[]>([]);
+  const [showLintIssues, setShowLintIssues] = useState(false);
 
   useEffect(() => {
     loadStateRates();
+    // The backend re-checks the table after every write
+    const unlisten = listen<LintReport>(LINT_EVENT, event => setLintIssues(event.payload.issues));
+    return () => {
+      unlisten.then(stop => stop());
+    };
   }, []);
 
   const loadStateRates = async () => {
     try {
       const rates = await invoke<StateRate[]>('get_all_state_rates');
       setStateRates(rates);
+      setSeedConflicts(await invoke<SeedConflict[]>('get_seed_conflicts_command'));
+      setOverdueCount((await invoke<StateRate[]>('get_overdue_state_rates_command')).length);
+      setLintIssues((await invoke<LintReport>('lint_rate_table_command')).issues);
       setError('');
     } catch (err) {
-      setError('Failed to load state rates: ' + err);
+      setError(describeError(err));
     } finally {
       setLoading(false);
     }
   };
 
-  const handleUpdate = async (stateRate: StateRate) => {
-    try {
-      await invoke('update_state_rate_command', { stateRate });
-      await loadStateRates();
-      setEditingRate(null);
-    } catch (err) {
-      setError('Failed to update state rate: ' + err);
-    }
+  // Errors are shown in the modal so a rejected row can be corrected
+  const handleSave = async (stateRate: StateRate, reason: string) => {
+    // Rows not yet in the database have no id
+    const command = stateRate.id ? 'update_state_rate_command' : 'create_state_rate_command';
+    await invoke(command, { stateRate, reason });
+    await loadStateRates();
+    setEditingRate(null);
   };
 
   const handleDelete = async (id: number) => {
-    if (window.confirm('Are you sure you want to delete this state rate?')) {
+    // Cancelling the prompt cancels the delete; the reason is optional
+    const reason = window.prompt('Move this state rate to the trash? Enter a reason (optional):', '');
+    if (reason !== null) {
+      try {
+        await invoke('delete_state_rate_command', { id, reason });
+        await loadStateRates();
+      } catch (err) {
+        setError(describeError(err));
+      }
+    }
+  };
+
+  const handleVerify = async (stateRate: StateRate) => {
+    // Cancelling the prompt cancels the verification; the note is optional
+    const note = window.prompt(
+      `Confirm ${stateRate.state}'s rate matches its source? Enter a verification note (optional):`,
+      '',
+    );
+    if (note !== null) {
       try {
-        await invoke('delete_state_rate_command', { id });
+        await invoke('verify_state_rate_command', { id: stateRate.id, note: note || null });
         await loadStateRates();
       } catch (err) {
-        setError('Failed to delete state rate: ' + err);
+        setError(describeError(err));
       }
     }
   };
 
+  // Resets the selected rows, or the whole table when none are selected
+  const handleReset = async () => {
+    const target = selectedStates.length > 0 ? selectedStates.join(', ') : 'the whole table';
+    const reason = window.prompt(
+      `Reset ${target} to the shipped rates? The current table is backed up first. Enter a reason (optional):`,
+      '',
+    );
+    if (reason === null) {
+      return;
+    }
+    try {
+      const report = await invoke<ResetReport>('reset_rate_table_command', {
+        states: selectedStates.length > 0 ? selectedStates : null,
+        reason: reason || null,
+      });
+      const trashed = report.trashed.length > 0 ? `; moved ${report.trashed.join(', ')} to the trash` : '';
+      setNotice(`Reset ${report.reset.length} rows${trashed}. The previous table was saved to ${report.backup}`);
+      setSelectedStates([]);
+      await loadStateRates();
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  // Saves the export through a download link; the webview has no save dialog
+  const handleExport = async (format: TableFormat) => {
+    try {
+      const contents = await invoke<string>('export_rate_table_command', { format });
+      const type = format === 'json' ? 'application/json' : 'text/csv';
+      const url = URL.createObjectURL(new Blob([contents], { type }));
+      const link = document.createElement('a');
+      link.href = url;
+      link.download = `state-rates-${new Date().toISOString().split('T')[0]}.${format}`;
+      link.click();
+      URL.revokeObjectURL(url);
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  const handleImportFile = async (e: React.ChangeEvent<HTMLInputElement>) => {
+    const file = e.target.files?.[0];
+    e.target.value = '';
+    if (file) {
+      const format: TableFormat = file.name.toLowerCase().endsWith('.csv') ? 'csv' : 'json';
+      setImportFile({ format, contents: await file.text() });
+    }
+  };
+
   const columnDefs: ColDef<StateRate>[] = useMemo(() => [
     { 
       field: 'state', 
       headerName: 'State',
       sortable: true,
       filter: true,
+      checkboxSelection: true,
+      headerCheckboxSelection: true,
       width: 200
     },
     { 
-      field: 'rate', 
-      headerName: 'Rate (%)',
-      sortable: true,
-      width: 100,
-      valueFormatter: params => params.value.toFixed(2) + '%'
-    },
-    { 
-      field: 'is_variable', 
-      headerName: 'Variable',
-      width: 100,
-      cellRenderer: (params: any) => params.value ? '✓' : ''
-    },
-    { 
-      field: 'plus_percentage', 
-      headerName: 'Plus %',
-      width: 100,
-      valueFormatter: params => params.value ? '+' + params.value.toFixed(2) + '%' : ''
+      field: 'formula', 
+      headerName: 'Rate',
+      width: 260,
+      valueFormatter: params => params.value ? describeFormula(params.value) : ''
     },
     { 
       field: 'update_frequency', 
       headerName: 'Update Freq',
-      width: 120
+      width: 120,
+      valueFormatter: params => params.value ? UPDATE_FREQUENCY_LABELS[params.value as UpdateFrequency] : ''
+    },
+    {
+      field: 'next_review',
+      headerName: 'Next Review',
+      width: 120,
+      valueFormatter: params => params.value ?? 'Unverified',
+      cellClass: params => params.data && isOverdue(params.data) ? 'text-red-600' : ''
     },
     { 
       field: 'last_update', 
@@ -94,7 +232,7 @@ const DbManagerTab: React.FC = () => {
     },
     {
       headerName: 'Actions',
-      width: 120,
+      width: 250,
       cellRenderer: (params: any) => (
         <div className="flex gap-2">
           <button
@@ -103,6 +241,18 @@ const DbManagerTab: React.FC = () => {
           >
             Edit
           </button>
+          <button
+            onClick={() => handleVerify(params.data)}
+            className="px-2 py-1 text-xs border border-gray-300 rounded hover:bg-gray-50"
+          >
+            Verify
+          </button>
+          <button
+            onClick={() => setHistoryRate(params.data)}
+            className="px-2 py-1 text-xs border border-gray-300 rounded hover:bg-gray-50"
+          >
+            History
+          </button>
           <button
             onClick={() => handleDelete(params.data.id)}
             className="px-2 py-1 text-xs bg-red-500 text-white rounded hover:bg-red-600"
@@ -126,15 +276,111 @@ const DbManagerTab: React.FC = () => {
     );
   }
 
+  const inconsistentRows = new Set(lintIssues.map(issue => issue.state_rate_id)).size;
+
   return (
     <div className="space-y-4">
       <div className="flex justify-between items-center">
         <h2 className="text-xl font-semibold text-gray-800">State Interest Rates Database</h2>
-        <div className="text-sm text-gray-600">
-          Total States: {stateRates.length}
+        <div className="flex items-center gap-4">
+          <div className="text-sm text-gray-600">
+            Total States: {stateRates.length}
+          </div>
+          <button
+            onClick={() => handleExport('json')}
+            className="px-3 py-1 text-sm border border-gray-300 rounded-md hover:bg-gray-50"
+          >
+            Export JSON
+          </button>
+          <button
+            onClick={() => handleExport('csv')}
+            className="px-3 py-1 text-sm border border-gray-300 rounded-md hover:bg-gray-50"
+          >
+            Export CSV
+          </button>
+          <label className="px-3 py-1 text-sm border border-gray-300 rounded-md hover:bg-gray-50 cursor-pointer">
+            Import
+            <input type="file" accept=".json,.csv" onChange={handleImportFile} className="hidden" />
+          </label>
+          <button
+            onClick={handleReset}
+            className="px-3 py-1 text-sm border border-gray-300 rounded-md hover:bg-gray-50"
+          >
+            {selectedStates.length > 0 ? `Reset Selected (${selectedStates.length})` : 'Reset to Defaults'}
+          </button>
+          <button
+            onClick={() => setShowOverdue(true)}
+            className={`px-3 py-1 text-sm border rounded-md ${overdueCount > 0 ? 'border-red-300 text-red-700 hover:bg-red-50' : 'border-gray-300 hover:bg-gray-50'}`}
+          >
+            Overdue ({overdueCount})
+          </button>
+          <button
+            onClick={() => setShowTrash(true)}
+            className="px-3 py-1 text-sm border border-gray-300 rounded-md hover:bg-gray-50"
+          >
+            Trash
+          </button>
+          <button
+            onClick={() => setEditingRate(newStateRate())}
+            className="px-3 py-1 text-sm bg-blue-600 text-white rounded-md hover:bg-blue-700"
+          >
+            Add Jurisdiction
+          </button>
         </div>
       </div>
 
+      {notice && (
+        <div className="flex justify-between items-center p-3 bg-green-50 border border-green-300 rounded-lg text-sm text-green-800">
+          <span>{notice}</span>
+          <button onClick={() => setNotice('')} className="px-2 hover:text-green-900">×</button>
+        </div>
+      )}
+
+      {seedConflicts.length > 0 && (
+        <div className="flex justify-between items-center p-3 bg-yellow-50 border border-yellow-300 rounded-lg text-sm text-yellow-800">
+          <span>
+            {seedConflicts.length} shipped rate {seedConflicts.length === 1 ? 'update was' : 'updates were'} not
+            applied because you had edited the row.
+          </span>
+          <button
+            onClick={() => setShowSeedConflicts(true)}
+            className="px-3 py-1 border border-yellow-400 rounded-md hover:bg-yellow-100"
+          >
+            Review
+          </button>
+        </div>
+      )}
+
+      {lintIssues.length > 0 && (
+        <div className="p-3 bg-orange-50 border border-orange-300 rounded-lg text-sm text-orange-800">
+          <div className="flex justify-between items-center">
+            <span>
+              {inconsistentRows} {inconsistentRows === 1 ? 'row has' : 'rows have'} fields that contradict each other.
+            </span>
+            <button
+              onClick={() => setShowLintIssues(!showLintIssues)}
+              className="px-3 py-1 border border-orange-400 rounded-md hover:bg-orange-100"
+            >
+              {showLintIssues ? 'Hide' : 'Show'}
+            </button>
+          </div>
+          {showLintIssues && (
+            <ul className="mt-2 list-disc list-inside space-y-1">
+              {lintIssues.map((issue, i) => (
+                <li key={i}>
+                  <button
+                    onClick={() => setEditingRate(stateRates.find(row => row.id === issue.state_rate_id) ?? null)}
+                    className="text-left hover:underline"
+                  >
+                    {issue.message}
+                  </button>
+                </li>
+              ))}
+            </ul>
+          )}
+        </div>
+      )}
+
       <div className="ag-theme-alpine" style={{ height: 500 }}>
         <AgGridReact
           rowData={stateRates}
@@ -144,109 +390,292 @@ const DbManagerTab: React.FC = () => {
           }}
           animateRows={true}
           rowHeight={40}
+          rowSelection="multiple"
+          suppressRowClickSelection={true}
+          onSelectionChanged={e => setSelectedStates(e.api.getSelectedRows().map(row => row.state))}
         />
       </div>
 
       {editingRate && (
         <EditModal
           stateRate={editingRate}
-          onSave={handleUpdate}
+          onSave={handleSave}
           onCancel={() => setEditingRate(null)}
         />
       )}
+
+      {importFile && (
+        <ImportModal
+          format={importFile.format}
+          contents={importFile.contents}
+          onImported={loadStateRates}
+          onClose={() => setImportFile(null)}
+        />
+      )}
+
+      {showSeedConflicts && (
+        <SeedConflictsModal
+          conflicts={seedConflicts}
+          onResolved={loadStateRates}
+          onClose={() => setShowSeedConflicts(false)}
+        />
+      )}
+
+      {showOverdue && (
+        <OverdueModal
+          onVerify={handleVerify}
+          onClose={() => setShowOverdue(false)}
+        />
+      )}
+
+      {showTrash && (
+        <TrashModal
+          onRestored={loadStateRates}
+          onClose={() => setShowTrash(false)}
+        />
+      )}
+
+      {historyRate && (
+        <HistoryModal
+          stateRate={historyRate}
+          onReverted={loadStateRates}
+          onClose={() => setHistoryRate(null)}
+        />
+      )}
     </div>
   );
 };
 
 interface EditModalProps {
   stateRate: StateRate;
-  onSave: (stateRate: StateRate) => void;
+  onSave: (stateRate: StateRate, reason: string) => Promise<void>;
   onCancel: () => void;
 }
 
 const EditModal: React.FC<EditModalProps> = ({ stateRate, onSave, onCancel }) => {
   const [formData, setFormData] = useState<StateRate>(stateRate);
+  const [reason, setReason] = useState('');
+  const [error, setError] = useState('');
 
-  const handleSubmit = (e: React.FormEvent) => {
+  const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
-    onSave({
-      ...formData,
-      last_update: new Date().toISOString().split('T')[0]
-    });
+    try {
+      await onSave({
+        ...formData,
+        last_update: new Date().toISOString().split('T')[0]
+      }, reason);
+    } catch (err) {
+      setError(describeError(err));
+    }
   };
 
   return (
     <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
-      <div className="bg-white rounded-lg p-6 max-w-md w-full">
-        <h3 className="text-lg font-semibold mb-4">Edit State Rate: {formData.state}</h3>
+      <div className="bg-white rounded-lg p-6 max-w-lg w-full max-h-screen overflow-y-auto">
+        <h3 className="text-lg font-semibold mb-4">
+          {stateRate.id ? `Edit State Rate: ${stateRate.state}` : 'Add Jurisdiction'}
+        </h3>
         
         <form onSubmit={handleSubmit} className="space-y-4">
           <div>
             <label className="block text-sm font-medium text-gray-700 mb-1">
-              Rate (%)
+              Name
             </label>
             <input
-              type="number"
-              value={formData.rate}
-              onChange={(e) => setFormData(prev => ({ ...prev, rate: parseFloat(e.target.value) || 0 }))}
-              step="0.01"
-              min="0"
+              type="text"
+              value={formData.state}
+              onChange={(e) => setFormData(prev => ({ ...prev, state: e.target.value }))}
+              required
               className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
             />
           </div>
 
           <div>
-            <label className="flex items-center space-x-2">
-              <input
-                type="checkbox"
-                checked={formData.is_variable}
-                onChange={(e) => setFormData(prev => ({ ...prev, is_variable: e.target.checked }))}
-                className="rounded border-gray-300 text-blue-600 focus:ring-blue-500"
-              />
-              <span className="text-sm font-medium text-gray-700">Variable Rate</span>
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              Rate Formula
             </label>
+            <RateFormulaEditor
+              formula={formData.formula}
+              onChange={(formula) => setFormData(prev => ({ ...prev, formula }))}
+            />
+            <p className="mt-1 text-xs text-gray-500">{describeFormula(formData.formula)}</p>
           </div>
 
-          {formData.is_variable && (
+          <div>
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              Rate Fixed On
+            </label>
+            <select
+              value={formData.rate_date_rule}
+              onChange={(e) => setFormData(prev => ({ ...prev, rate_date_rule: e.target.value as RateDateRule }))}
+              className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+              <option value="preceding_week">Average of the week preceding judgment</option>
+              <option value="january_first">January 1 of the judgment year</option>
+              <option value="half_year_first_business_day">First business day of the half-year</option>
+              <option value="verdict_date">Date of verdict</option>
+              <option value="entry_date">Date of entry of judgment</option>
+            </select>
+          </div>
+
+          <div>
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              Compounding
+            </label>
+            <select
+              value={formData.compounding}
+              onChange={(e) => setFormData(prev => ({ ...prev, compounding: e.target.value as CompoundingMode }))}
+              className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+              <option value="none">None (simple interest)</option>
+              <option value="annual">Annual (January 1)</option>
+              <option value="semi_annual">Semi-Annual (January 1 / July 1)</option>
+              <option value="monthly">Monthly</option>
+              <option value="daily">Daily</option>
+              <option value="anniversary">Judgment Anniversary</option>
+            </select>
+          </div>
+
+          <div>
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              Day Count
+            </label>
+            <select
+              value={formData.day_count}
+              onChange={(e) => setFormData(prev => ({ ...prev, day_count: e.target.value as DayCount }))}
+              className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+              <option value="actual_365">Actual/365</option>
+              <option value="actual_actual">Actual/Actual</option>
+              <option value="actual_360">Actual/360</option>
+              <option value="thirty_360">30/360</option>
+            </select>
+          </div>
+
+          <div>
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              Rounding
+            </label>
+            <div className="grid grid-cols-2 gap-2">
+              <select
+                value={formData.rounding.stage}
+                onChange={(e) => setFormData(prev => ({ ...prev, rounding: { ...prev.rounding, stage: e.target.value as RoundingStage } }))}
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              >
+                <option value="per_day">Per Day</option>
+                <option value="per_period">Per Period</option>
+                <option value="final">Final Total Only</option>
+              </select>
+              <select
+                value={formData.rounding.mode}
+                onChange={(e) => setFormData(prev => ({ ...prev, rounding: { ...prev.rounding, mode: e.target.value as RoundingMode } }))}
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              >
+                <option value="half_up">Half Up</option>
+                <option value="half_even">Half Even (Banker's)</option>
+              </select>
+            </div>
+          </div>
+
+          <div>
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              Update Frequency
+            </label>
+            <select
+              value={formData.update_frequency}
+              onChange={(e) => setFormData(prev => ({ ...prev, update_frequency: e.target.value as UpdateFrequency }))}
+              className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+              {(Object.keys(UPDATE_FREQUENCY_LABELS) as UpdateFrequency[]).map(frequency => (
+                <option key={frequency} value={frequency}>{UPDATE_FREQUENCY_LABELS[frequency]}</option>
+              ))}
+            </select>
+            <p className="mt-1 text-xs text-gray-500">
+              {formData.verified_on
+                ? `Verified by ${formData.verified_by} on ${formData.verified_on}; next review ${formData.next_review}. Changing the rate clears the verification.`
+                : 'Not yet verified; use Verify in the table once the rate is checked against its source.'}
+            </p>
+          </div>
+
+          <div>
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              Notes
+            </label>
+            <textarea
+              value={formData.notes}
+              onChange={(e) => setFormData(prev => ({ ...prev, notes: e.target.value }))}
+              rows={3}
+              className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+            />
+          </div>
+
+          <div className="grid grid-cols-2 gap-4">
             <div>
               <label className="block text-sm font-medium text-gray-700 mb-1">
-                Plus Percentage (%)
+                Statute Citation
               </label>
               <input
-                type="number"
-                value={formData.plus_percentage}
-                onChange={(e) => setFormData(prev => ({ ...prev, plus_percentage: parseFloat(e.target.value) || 0 }))}
-                step="0.01"
-                min="0"
+                type="text"
+                value={formData.citation}
+                onChange={(e) => setFormData(prev => ({ ...prev, citation: e.target.value }))}
+                placeholder="e.g. Fla. Stat. § 55.03"
                 className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
               />
             </div>
-          )}
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">
+                Effective Date
+              </label>
+              <input
+                type="date"
+                value={formData.effective_date ?? ''}
+                onChange={(e) => setFormData(prev => ({ ...prev, effective_date: e.target.value || null }))}
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              />
+            </div>
+          </div>
 
           <div>
             <label className="block text-sm font-medium text-gray-700 mb-1">
-              Update Frequency
+              Source URL
+            </label>
+            <input
+              type="url"
+              value={formData.source_url}
+              onChange={(e) => setFormData(prev => ({ ...prev, source_url: e.target.value }))}
+              placeholder="https://"
+              className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+            />
+          </div>
+
+          <div>
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              Verification Note
             </label>
             <input
               type="text"
-              value={formData.update_frequency}
-              onChange={(e) => setFormData(prev => ({ ...prev, update_frequency: e.target.value }))}
+              value={formData.verification_note}
+              onChange={(e) => setFormData(prev => ({ ...prev, verification_note: e.target.value }))}
+              placeholder="Who checked the rate against the source, and when"
               className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
             />
           </div>
 
           <div>
             <label className="block text-sm font-medium text-gray-700 mb-1">
-              Notes
+              Reason for Change
             </label>
-            <textarea
-              value={formData.notes}
-              onChange={(e) => setFormData(prev => ({ ...prev, notes: e.target.value }))}
-              rows={3}
+            <input
+              type="text"
+              value={reason}
+              onChange={(e) => setReason(e.target.value)}
+              placeholder="Optional; kept in the history"
               className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
             />
           </div>
 
+          {error && <p className="text-sm text-red-700">{error}</p>}
+
           <div className="flex justify-end space-x-2 pt-4">
             <button
               type="button"
@@ -259,7 +688,7 @@ const EditModal: React.FC<EditModalProps> = ({ stateRate, onSave, onCancel }) =>
               type="submit"
               className="px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700"
             >
-              Save Changes
+              {stateRate.id ? 'Save Changes' : 'Add'}
             </button>
           </div>
         </form>
@@ -268,4 +697,494 @@ const EditModal: React.FC<EditModalProps> = ({ stateRate, onSave, onCancel }) =>
   );
 };
 
+interface ImportModalProps {
+  format: TableFormat;
+  contents: string;
+  onImported: () => Promise<void>;
+  onClose: () => void;
+}
+
+// Shows what an import file would change and applies it on confirmation
+const ImportModal: React.FC<ImportModalProps> = ({ format, contents, onImported, onClose }) => {
+  const [preview, setPreview] = useState<ImportPreview | null>(null);
+  const [reason, setReason] = useState('');
+  const [error, setError] = useState('');
+
+  useEffect(() => {
+    invoke<ImportPreview>('preview_rate_table_import_command', { format, contents })
+      .then(setPreview)
+      .catch(err => setError(describeError(err)));
+  }, [format, contents]);
+
+  const handleApply = async () => {
+    try {
+      await invoke('import_rate_table_command', { format, contents, reason: reason || null });
+      await onImported();
+      onClose();
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  const hasChanges = preview && (preview.added.length + preview.changed.length + preview.removed.length) > 0;
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+      <div className="bg-white rounded-lg p-6 max-w-2xl w-full max-h-screen overflow-y-auto space-y-4">
+        <h3 className="text-lg font-semibold">Import Rate Table ({format.toUpperCase()})</h3>
+
+        {error && <p className="text-sm text-red-700">{error}</p>}
+
+        {preview && (
+          <div className="space-y-3 text-sm">
+            <p className="text-gray-600">{preview.unchanged} unchanged</p>
+            {preview.added.length > 0 && (
+              <div>
+                <p className="font-medium text-green-700">Added ({preview.added.length})</p>
+                <p>{preview.added.join(', ')}</p>
+              </div>
+            )}
+            {preview.changed.length > 0 && (
+              <div>
+                <p className="font-medium text-blue-700">Changed ({preview.changed.length})</p>
+                <ul className="list-disc pl-5">
+                  {preview.changed.map(change => (
+                    <li key={change.state}>{change.state}: {change.fields.join(', ')}</li>
+                  ))}
+                </ul>
+              </div>
+            )}
+            {preview.removed.length > 0 && (
+              <div>
+                <p className="font-medium text-red-700">Moved to trash ({preview.removed.length})</p>
+                <p>{preview.removed.join(', ')}</p>
+              </div>
+            )}
+            {hasChanges && (
+              <input
+                type="text"
+                value={reason}
+                onChange={(e) => setReason(e.target.value)}
+                placeholder="Reason (optional; kept in the history)"
+                className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              />
+            )}
+          </div>
+        )}
+
+        <div className="flex justify-end space-x-2">
+          <button
+            onClick={onClose}
+            className="px-4 py-2 border border-gray-300 rounded-md hover:bg-gray-50"
+          >
+            Cancel
+          </button>
+          <button
+            onClick={handleApply}
+            disabled={!hasChanges}
+            className="px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700 disabled:bg-gray-400"
+          >
+            Apply Import
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+};
+
+interface SeedConflictsModalProps {
+  conflicts: SeedConflict[];
+  onResolved: () => Promise<void>;
+  onClose: () => void;
+}
+
+// Shipped values next to the user's edits, to take one or the other
+const SeedConflictsModal: React.FC<SeedConflictsModalProps> = ({ conflicts, onResolved, onClose }) => {
+  const [error, setError] = useState('');
+
+  const handleResolve = async (conflict: SeedConflict, acceptShipped: boolean) => {
+    try {
+      await invoke('resolve_seed_conflict_command', {
+        state: conflict.shipped.state,
+        acceptShipped,
+        reason: acceptShipped ? `Accepted shipped rate table v${conflict.seed_version}` : null,
+      });
+      await onResolved();
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+      <div className="bg-white rounded-lg p-6 max-w-3xl w-full max-h-screen overflow-y-auto">
+        <h3 className="text-lg font-semibold mb-4">Shipped Rate Updates</h3>
+
+        {error && <p className="text-sm text-red-700 mb-2">{error}</p>}
+
+        {conflicts.length === 0 ? (
+          <p className="text-sm text-gray-500">Nothing left to review.</p>
+        ) : (
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="text-left text-gray-500 border-b">
+                <th className="py-1 pr-2">State</th>
+                <th className="py-1 pr-2">Yours</th>
+                <th className="py-1 pr-2">Shipped</th>
+                <th className="py-1"></th>
+              </tr>
+            </thead>
+            <tbody>
+              {conflicts.map(conflict => (
+                <tr key={conflict.shipped.state} className="border-b align-top">
+                  <td className="py-1 pr-2">
+                    {conflict.shipped.state}
+                    <p className="text-xs text-gray-500">{conflict.fields.join(', ')}</p>
+                  </td>
+                  <td className="py-1 pr-2">
+                    {describeFormula(conflict.local.formula)}
+                    <p className="text-xs text-gray-500">{conflict.local.notes}</p>
+                  </td>
+                  <td className="py-1 pr-2">
+                    {describeFormula(conflict.shipped.formula)}
+                    <p className="text-xs text-gray-500">{conflict.shipped.notes}</p>
+                  </td>
+                  <td className="py-1 text-right space-x-2 whitespace-nowrap">
+                    <button
+                      onClick={() => handleResolve(conflict, true)}
+                      className="px-2 py-1 text-xs bg-blue-500 text-white rounded hover:bg-blue-600"
+                    >
+                      Use Shipped
+                    </button>
+                    <button
+                      onClick={() => handleResolve(conflict, false)}
+                      className="px-2 py-1 text-xs border border-gray-300 rounded hover:bg-gray-50"
+                    >
+                      Keep Mine
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+
+        <div className="flex justify-end pt-4">
+          <button
+            onClick={onClose}
+            className="px-4 py-2 border border-gray-300 rounded-md hover:bg-gray-50"
+          >
+            Close
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+};
+
+interface OverdueModalProps {
+  onVerify: (stateRate: StateRate) => Promise<void>;
+  onClose: () => void;
+}
+
+// Rows never verified or past their review date, most overdue first
+const OverdueModal: React.FC<OverdueModalProps> = ({ onVerify, onClose }) => {
+  const [overdue, setOverdue] = useState<StateRate[]>([]);
+  const [error, setError] = useState('');
+
+  const loadOverdue = async () => {
+    try {
+      setOverdue(await invoke<StateRate[]>('get_overdue_state_rates_command'));
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  useEffect(() => {
+    loadOverdue();
+  }, []);
+
+  const handleVerify = async (stateRate: StateRate) => {
+    await onVerify(stateRate);
+    await loadOverdue();
+  };
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+      <div className="bg-white rounded-lg p-6 max-w-2xl w-full max-h-screen overflow-y-auto">
+        <h3 className="text-lg font-semibold mb-4">Due for Verification</h3>
+
+        {error && <p className="text-sm text-red-700 mb-2">{error}</p>}
+
+        {overdue.length === 0 ? (
+          <p className="text-sm text-gray-500">Every rate is verified and current.</p>
+        ) : (
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="text-left text-gray-500 border-b">
+                <th className="py-1 pr-2">State</th>
+                <th className="py-1 pr-2">Last Verified</th>
+                <th className="py-1 pr-2">Due</th>
+                <th className="py-1"></th>
+              </tr>
+            </thead>
+            <tbody>
+              {overdue.map(stateRate => (
+                <tr key={stateRate.id} className="border-b">
+                  <td className="py-1 pr-2">{stateRate.state}</td>
+                  <td className="py-1 pr-2">
+                    {stateRate.verified_on ? `${stateRate.verified_on} by ${stateRate.verified_by}` : 'Never'}
+                  </td>
+                  <td className="py-1 pr-2 whitespace-nowrap">{stateRate.next_review ?? 'Now'}</td>
+                  <td className="py-1 text-right">
+                    <button
+                      onClick={() => handleVerify(stateRate)}
+                      className="px-2 py-1 text-xs bg-blue-500 text-white rounded hover:bg-blue-600"
+                    >
+                      Verify
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+
+        <div className="flex justify-end pt-4">
+          <button
+            onClick={onClose}
+            className="px-4 py-2 border border-gray-300 rounded-md hover:bg-gray-50"
+          >
+            Close
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+};
+
+interface TrashModalProps {
+  onRestored: () => Promise<void>;
+  onClose: () => void;
+}
+
+// Deleted jurisdictions, which can be restored or purged for good
+const TrashModal: React.FC<TrashModalProps> = ({ onRestored, onClose }) => {
+  const [trashed, setTrashed] = useState<StateRate[]>([]);
+  const [error, setError] = useState('');
+
+  const loadTrash = async () => {
+    try {
+      setTrashed(await invoke<StateRate[]>('get_trashed_state_rates_command'));
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  useEffect(() => {
+    loadTrash();
+  }, []);
+
+  const handleRestore = async (id: number) => {
+    try {
+      await invoke('restore_state_rate_command', { id, reason: null });
+      await onRestored();
+      await loadTrash();
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  const handlePurge = async (stateRate: StateRate) => {
+    const reason = window.prompt(
+      `Permanently delete ${stateRate.state} and its rate history? Enter a reason (optional):`,
+      '',
+    );
+    if (reason === null) {
+      return;
+    }
+    try {
+      await invoke('purge_state_rate_command', { id: stateRate.id, reason });
+      await loadTrash();
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+      <div className="bg-white rounded-lg p-6 max-w-2xl w-full max-h-screen overflow-y-auto">
+        <h3 className="text-lg font-semibold mb-4">Trash</h3>
+
+        {error && <p className="text-sm text-red-700 mb-2">{error}</p>}
+
+        {trashed.length === 0 ? (
+          <p className="text-sm text-gray-500">The trash is empty.</p>
+        ) : (
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="text-left text-gray-500 border-b">
+                <th className="py-1 pr-2">State</th>
+                <th className="py-1 pr-2">Rate</th>
+                <th className="py-1 pr-2">Deleted</th>
+                <th className="py-1"></th>
+              </tr>
+            </thead>
+            <tbody>
+              {trashed.map(stateRate => (
+                <tr key={stateRate.id} className="border-b">
+                  <td className="py-1 pr-2">{stateRate.state}</td>
+                  <td className="py-1 pr-2">{describeFormula(stateRate.formula)}</td>
+                  <td className="py-1 pr-2 whitespace-nowrap">{stateRate.deleted_at}</td>
+                  <td className="py-1 text-right space-x-2">
+                    <button
+                      onClick={() => handleRestore(stateRate.id)}
+                      className="px-2 py-1 text-xs bg-blue-500 text-white rounded hover:bg-blue-600"
+                    >
+                      Restore
+                    </button>
+                    <button
+                      onClick={() => handlePurge(stateRate)}
+                      className="px-2 py-1 text-xs bg-red-500 text-white rounded hover:bg-red-600"
+                    >
+                      Delete Forever
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+
+        <div className="flex justify-end pt-4">
+          <button
+            onClick={onClose}
+            className="px-4 py-2 border border-gray-300 rounded-md hover:bg-gray-50"
+          >
+            Close
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+};
+
+interface HistoryModalProps {
+  stateRate: StateRate;
+  onReverted: () => Promise<void>;
+  onClose: () => void;
+}
+
+// Lists a row's audit trail and restores any snapshot in it
+const HistoryModal: React.FC<HistoryModalProps> = ({ stateRate, onReverted, onClose }) => {
+  const [entries, setEntries] = useState<StateRateAudit[]>([]);
+  const [error, setError] = useState('');
+
+  const loadHistory = async () => {
+    try {
+      setEntries(await invoke<StateRateAudit[]>('get_state_rate_history_command', { stateRateId: stateRate.id }));
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  useEffect(() => {
+    loadHistory();
+  }, [stateRate.id]);
+
+  const handleRevert = async (entry: StateRateAudit, snapshot: AuditSnapshot) => {
+    const reason = window.prompt('Restore this version? Enter a reason (optional):', '');
+    if (reason === null) {
+      return;
+    }
+    try {
+      await invoke('revert_state_rate_command', { auditId: entry.id, snapshot, reason });
+      await onReverted();
+      await loadHistory();
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  const describeSnapshot = (row: StateRate | null) =>
+    row ? `${describeFormula(row.formula)} (${UPDATE_FREQUENCY_LABELS[row.update_frequency]})` : '—';
+
+  const snapshotPeriods = (entry: StateRateAudit, snapshot: AuditSnapshot) =>
+    snapshot === 'before' ? entry.before_periods : entry.after_periods;
+
+  const describePeriods = (periods: StateRatePeriod[]) =>
+    periods.length === 0
+      ? 'No rate periods'
+      : periods
+          .map(period => {
+            const until = period.effective_to ? ` to ${period.effective_to}` : '';
+            return `${period.rate}% from ${period.effective_from}${until}`;
+          })
+          .join('; ');
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+      <div className="bg-white rounded-lg p-6 max-w-4xl w-full max-h-screen overflow-y-auto">
+        <h3 className="text-lg font-semibold mb-4">History: {stateRate.state}</h3>
+
+        {error && <p className="text-sm text-red-700 mb-2">{error}</p>}
+
+        {entries.length === 0 ? (
+          <p className="text-sm text-gray-500">No changes recorded.</p>
+        ) : (
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="text-left text-gray-500 border-b">
+                <th className="py-1 pr-2">When</th>
+                <th className="py-1 pr-2">Who</th>
+                <th className="py-1 pr-2">Change</th>
+                <th className="py-1 pr-2">Before</th>
+                <th className="py-1 pr-2">After</th>
+                <th className="py-1">Reason</th>
+              </tr>
+            </thead>
+            <tbody>
+              {entries.map(entry => (
+                <tr key={entry.id} className="border-b align-top">
+                  <td className="py-1 pr-2 whitespace-nowrap">{entry.changed_at}</td>
+                  <td className="py-1 pr-2">{entry.changed_by}</td>
+                  <td className="py-1 pr-2 capitalize">{entry.action}</td>
+                  {(['before', 'after'] as AuditSnapshot[]).map(snapshot => (
+                    <td key={snapshot} className="py-1 pr-2">
+                      <div>{describeSnapshot(entry[snapshot])}</div>
+                      {snapshotPeriods(entry, snapshot) && (
+                        <div className="text-xs text-gray-500">
+                          {describePeriods(snapshotPeriods(entry, snapshot)!)}
+                        </div>
+                      )}
+                      {entry[snapshot] && (
+                        <button
+                          onClick={() => handleRevert(entry, snapshot)}
+                          className="text-xs text-blue-600 hover:underline"
+                        >
+                          Restore
+                        </button>
+                      )}
+                    </td>
+                  ))}
+                  <td className="py-1">{entry.reason ?? ''}</td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+
+        <div className="flex justify-end pt-4">
+          <button
+            onClick={onClose}
+            className="px-4 py-2 border border-gray-300 rounded-md hover:bg-gray-50"
+          >
+            Close
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+};
+
 export default DbManagerTab;
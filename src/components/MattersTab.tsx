@@ -0,0 +1,448 @@
+import React, { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { describeError } from '../errors';
+import { CalculationSummary, Decimal, Matter, SavedCalculation, StateRate } from '../types';
+
+interface MattersTabProps {
+  onOpen: (calculation: SavedCalculation) => void;
+}
+
+const today = () => new Date().toISOString().split('T')[0];
+
+const newMatter = (): Matter => ({
+  id: 0,
+  caption: '',
+  docket_number: '',
+  court: '',
+  client: '',
+  judgment_date: today(),
+  principal: '0',
+  jurisdiction: 'Federal',
+  created_at: '',
+  updated_at: '',
+  calculation_count: 0,
+});
+
+const formatCurrency = (amount: Decimal) =>
+  new Intl.NumberFormat('en-US', { style: 'currency', currency: 'USD' }).format(Number(amount));
+
+const MattersTab: React.FC<MattersTabProps> = ({ onOpen }) => {
+  const [matters, setMatters] = useState<Matter[]>([]);
+  const [selectedMatter, setSelectedMatter] = useState<Matter | null>(null);
+  const [calculations, setCalculations] = useState<CalculationSummary[]>([]);
+  const [editingMatter, setEditingMatter] = useState<Matter | null>(null);
+  const [filter, setFilter] = useState('');
+  const [error, setError] = useState('');
+
+  useEffect(() => {
+    loadMatters();
+  }, []);
+
+  const loadMatters = async () => {
+    try {
+      setMatters(await invoke<Matter[]>('get_matters_command'));
+      setError('');
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  const loadCalculations = async (matter: Matter) => {
+    try {
+      setCalculations(await invoke<CalculationSummary[]>('get_calculations_command', { matterId: matter.id }));
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  const selectMatter = async (matter: Matter) => {
+    setSelectedMatter(matter);
+    await loadCalculations(matter);
+  };
+
+  // Errors are shown in the modal so a rejected matter can be corrected
+  const handleSave = async (matter: Matter) => {
+    const command = matter.id ? 'update_matter_command' : 'create_matter_command';
+    const saved = await invoke<Matter>(command, { matter });
+    await loadMatters();
+    setSelectedMatter(saved);
+    await loadCalculations(saved);
+    setEditingMatter(null);
+  };
+
+  const handleDeleteMatter = async (matter: Matter) => {
+    const count = matter.calculation_count;
+    const message = count
+      ? `Delete ${matter.caption} and its ${count} saved ${count === 1 ? 'calculation' : 'calculations'}?`
+      : `Delete ${matter.caption}?`;
+    if (!window.confirm(message)) {
+      return;
+    }
+    try {
+      await invoke('delete_matter_command', { id: matter.id });
+      setSelectedMatter(null);
+      setCalculations([]);
+      await loadMatters();
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  const handleOpen = async (id: number) => {
+    try {
+      onOpen(await invoke<SavedCalculation>('get_calculation_command', { id }));
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  const handleRename = async (calculation: CalculationSummary) => {
+    const name = window.prompt('Rename this calculation:', calculation.name);
+    if (name === null || name === calculation.name) {
+      return;
+    }
+    try {
+      await invoke('rename_calculation_command', { id: calculation.id, name });
+      if (selectedMatter) {
+        await loadCalculations(selectedMatter);
+      }
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  // The re-run is saved beside the original, which is kept as it was filed
+  const handleRerun = async (calculation: CalculationSummary) => {
+    const toDate = window.prompt('Re-run with current rates through (YYYY-MM-DD):', today());
+    if (toDate === null) {
+      return;
+    }
+    try {
+      const rerun = await invoke<SavedCalculation>('rerun_calculation_command', {
+        id: calculation.id,
+        toDate: toDate || null,
+      });
+      await loadMatters();
+      onOpen(rerun);
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  const handleDeleteCalculation = async (calculation: CalculationSummary) => {
+    if (!window.confirm(`Delete the saved calculation "${calculation.name}"?`)) {
+      return;
+    }
+    try {
+      await invoke('delete_calculation_command', { id: calculation.id });
+      await loadMatters();
+      if (selectedMatter) {
+        await loadCalculations(selectedMatter);
+      }
+    } catch (err) {
+      setError(describeError(err));
+    }
+  };
+
+  const search = filter.trim().toLowerCase();
+  const shownMatters = matters.filter(matter =>
+    !search
+    || [matter.caption, matter.docket_number, matter.court, matter.client, matter.jurisdiction]
+      .some(field => field.toLowerCase().includes(search)),
+  );
+
+  return (
+    <div className="space-y-4">
+      <div className="flex justify-between items-center gap-2">
+        <input
+          type="text"
+          value={filter}
+          onChange={(e) => setFilter(e.target.value)}
+          placeholder="Search by caption, docket, court, client or jurisdiction"
+          className="flex-1 px-3 py-1 text-sm border border-gray-300 rounded-md"
+        />
+        <button
+          onClick={() => setEditingMatter(newMatter())}
+          className="px-3 py-1 text-sm bg-blue-600 text-white rounded-md hover:bg-blue-700"
+        >
+          New Matter
+        </button>
+      </div>
+
+      {error && (
+        <div className="p-3 bg-red-100 border border-red-400 rounded-lg text-sm text-red-700">{error}</div>
+      )}
+
+      {matters.length === 0 ? (
+        <p className="text-sm text-gray-500">No matters yet. Create one to save calculations against it.</p>
+      ) : (
+        <table className="w-full text-sm">
+          <thead>
+            <tr className="text-left text-gray-500 border-b">
+              <th className="py-1 pr-2">Caption</th>
+              <th className="py-1 pr-2">Docket</th>
+              <th className="py-1 pr-2">Court</th>
+              <th className="py-1 pr-2">Client</th>
+              <th className="py-1 pr-2">Judgment</th>
+              <th className="py-1 pr-2">Principal</th>
+              <th className="py-1 pr-2">Jurisdiction</th>
+              <th className="py-1 pr-2">Saved</th>
+              <th className="py-1"></th>
+            </tr>
+          </thead>
+          <tbody>
+            {shownMatters.map(matter => (
+              <tr
+                key={matter.id}
+                onClick={() => selectMatter(matter)}
+                className={`border-b cursor-pointer ${selectedMatter?.id === matter.id ? 'bg-blue-50' : 'hover:bg-gray-50'}`}
+              >
+                <td className="py-1 pr-2 font-medium">{matter.caption}</td>
+                <td className="py-1 pr-2">{matter.docket_number}</td>
+                <td className="py-1 pr-2">{matter.court}</td>
+                <td className="py-1 pr-2">{matter.client}</td>
+                <td className="py-1 pr-2 whitespace-nowrap">{matter.judgment_date}</td>
+                <td className="py-1 pr-2">{formatCurrency(matter.principal)}</td>
+                <td className="py-1 pr-2">{matter.jurisdiction}</td>
+                <td className="py-1 pr-2">{matter.calculation_count}</td>
+                <td className="py-1 text-right whitespace-nowrap">
+                  <button
+                    onClick={(e) => { e.stopPropagation(); setEditingMatter(matter); }}
+                    className="px-2 py-1 text-xs bg-blue-500 text-white rounded hover:bg-blue-600 mr-1"
+                  >
+                    Edit
+                  </button>
+                  <button
+                    onClick={(e) => { e.stopPropagation(); handleDeleteMatter(matter); }}
+                    className="px-2 py-1 text-xs bg-red-500 text-white rounded hover:bg-red-600"
+                  >
+                    Delete
+                  </button>
+                </td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      )}
+
+      {selectedMatter && (
+        <div className="bg-gray-50 p-4 rounded-lg">
+          <h3 className="font-semibold text-gray-800 mb-2">Saved Calculations: {selectedMatter.caption}</h3>
+          {calculations.length === 0 ? (
+            <p className="text-sm text-gray-500">
+              None yet. Run a calculation on the Calculator tab and save it to this matter.
+            </p>
+          ) : (
+            <table className="w-full text-sm">
+              <thead>
+                <tr className="text-left text-gray-500 border-b">
+                  <th className="py-1 pr-2">Name</th>
+                  <th className="py-1 pr-2">Period</th>
+                  <th className="py-1 pr-2">Rate</th>
+                  <th className="py-1 pr-2">Total</th>
+                  <th className="py-1 pr-2">Saved</th>
+                  <th className="py-1"></th>
+                </tr>
+              </thead>
+              <tbody>
+                {calculations.map(calculation => (
+                  <tr key={calculation.id} className="border-b">
+                    <td className="py-1 pr-2">{calculation.name}</td>
+                    <td className="py-1 pr-2 whitespace-nowrap">
+                      {calculation.from_date} to {calculation.to_date}
+                    </td>
+                    <td className="py-1 pr-2">{Number(calculation.rate).toFixed(2)}%</td>
+                    <td className="py-1 pr-2">{formatCurrency(calculation.total_amount)}</td>
+                    <td className="py-1 pr-2 whitespace-nowrap">{calculation.calculated_at}</td>
+                    <td className="py-1 text-right whitespace-nowrap space-x-1">
+                      <button
+                        onClick={() => handleOpen(calculation.id)}
+                        className="px-2 py-1 text-xs bg-blue-500 text-white rounded hover:bg-blue-600"
+                      >
+                        Open
+                      </button>
+                      <button
+                        onClick={() => handleRename(calculation)}
+                        className="px-2 py-1 text-xs border border-gray-300 rounded hover:bg-gray-100"
+                      >
+                        Rename
+                      </button>
+                      <button
+                        onClick={() => handleRerun(calculation)}
+                        className="px-2 py-1 text-xs border border-gray-300 rounded hover:bg-gray-100"
+                      >
+                        Re-run
+                      </button>
+                      <button
+                        onClick={() => handleDeleteCalculation(calculation)}
+                        className="px-2 py-1 text-xs bg-red-500 text-white rounded hover:bg-red-600"
+                      >
+                        Delete
+                      </button>
+                    </td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          )}
+        </div>
+      )}
+
+      {editingMatter && (
+        <MatterModal
+          matter={editingMatter}
+          onSave={handleSave}
+          onCancel={() => setEditingMatter(null)}
+        />
+      )}
+    </div>
+  );
+};
+
+interface MatterModalProps {
+  matter: Matter;
+  onSave: (matter: Matter) => Promise<void>;
+  onCancel: () => void;
+}
+
+const MatterModal: React.FC<MatterModalProps> = ({ matter, onSave, onCancel }) => {
+  const [formData, setFormData] = useState<Matter>(matter);
+  const [jurisdictions, setJurisdictions] = useState<string[]>([]);
+  const [error, setError] = useState('');
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    invoke<StateRate[]>('get_all_state_rates')
+      .then(rates => {
+        const names = rates.map(rate => rate.state).filter(state => state !== 'Federal');
+        setJurisdictions(['Federal', ...names]);
+      })
+      .catch(err => setError(describeError(err)));
+  }, []);
+
+  const setField = (field: keyof Matter, value: string) =>
+    setFormData(prev => ({ ...prev, [field]: value }));
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    setSaving(true);
+    try {
+      await onSave(formData);
+    } catch (err) {
+      setError(describeError(err));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const inputClass =
+    'w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500';
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+      <div className="bg-white rounded-lg p-6 max-w-lg w-full max-h-screen overflow-y-auto">
+        <h3 className="text-lg font-semibold mb-4">{matter.id ? 'Edit Matter' : 'New Matter'}</h3>
+
+        <form onSubmit={handleSubmit} className="space-y-3">
+          <div>
+            <label className="block text-sm font-medium text-gray-700 mb-1">Case Caption</label>
+            <input
+              type="text"
+              value={formData.caption}
+              onChange={(e) => setField('caption', e.target.value)}
+              placeholder="Smith v. Jones"
+              className={inputClass}
+              required
+            />
+          </div>
+          <div className="grid grid-cols-2 gap-3">
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">Docket Number</label>
+              <input
+                type="text"
+                value={formData.docket_number}
+                onChange={(e) => setField('docket_number', e.target.value)}
+                className={inputClass}
+              />
+            </div>
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">Court</label>
+              <input
+                type="text"
+                value={formData.court}
+                onChange={(e) => setField('court', e.target.value)}
+                className={inputClass}
+              />
+            </div>
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">Client</label>
+              <input
+                type="text"
+                value={formData.client}
+                onChange={(e) => setField('client', e.target.value)}
+                className={inputClass}
+              />
+            </div>
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">Jurisdiction</label>
+              <select
+                value={formData.jurisdiction}
+                onChange={(e) => setField('jurisdiction', e.target.value)}
+                className={inputClass}
+              >
+                {!jurisdictions.includes(formData.jurisdiction) && (
+                  <option value={formData.jurisdiction}>{formData.jurisdiction}</option>
+                )}
+                {jurisdictions.map(name => (
+                  <option key={name} value={name}>{name}</option>
+                ))}
+              </select>
+            </div>
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">Judgment Date</label>
+              <input
+                type="date"
+                value={formData.judgment_date}
+                onChange={(e) => setField('judgment_date', e.target.value)}
+                className={inputClass}
+                required
+              />
+            </div>
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-1">Principal</label>
+              <input
+                type="number"
+                step="0.01"
+                min="0"
+                value={formData.principal}
+                onChange={(e) => setField('principal', e.target.value || '0')}
+                className={inputClass}
+              />
+            </div>
+          </div>
+
+          {error && <p className="text-sm text-red-700">{error}</p>}
+
+          <div className="flex justify-end gap-2 pt-2">
+            <button
+              type="button"
+              onClick={onCancel}
+              className="px-4 py-2 border border-gray-300 rounded-md hover:bg-gray-50"
+            >
+              Cancel
+            </button>
+            <button
+              type="submit"
+              disabled={saving}
+              className="px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700 disabled:bg-gray-400"
+            >
+              {saving ? 'Saving...' : 'Save'}
+            </button>
+          </div>
+        </form>
+      </div>
+    </div>
+  );
+};
+
+export default MattersTab;
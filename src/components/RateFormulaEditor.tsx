@@ -0,0 +1,194 @@
+import React from 'react';
+import { Decimal, RateBase, RateFormula, RateIndex } from '../types';
+
+const INDEX_LABELS: Record<RateIndex, string> = {
+  DGS1: '1-Year Treasury',
+  DPRIME: 'Prime Rate',
+  DPCREDIT: 'Discount Rate',
+  DFF: 'Federal Funds Rate',
+};
+
+const inputClass =
+  'w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500';
+
+// Mirrors RateFormula::describe in the backend
+export const describeFormula = (formula: RateFormula): string => {
+  const base = formula.base;
+  let description =
+    base.kind === 'fixed' ? `${Number(base.rate)}%`
+    : base.kind === 'index' ? INDEX_LABELS[base.index]
+    : `${base.kind === 'greater_of' ? 'greater' : 'lesser'} of (${base.formulas.map(describeFormula).join(', ')})`;
+
+  if (formula.rounding_increment) {
+    description += ` rounded to ${Number(formula.rounding_increment)}%`;
+  }
+  if (Number(formula.spread)) {
+    const spread = Number(formula.spread);
+    description += ` ${spread < 0 ? '-' : '+'} ${Math.abs(spread)}%`;
+  }
+  if (formula.floor) {
+    description += `, min ${Number(formula.floor)}%`;
+  }
+  if (formula.cap) {
+    description += `, max ${Number(formula.cap)}%`;
+  }
+  return description;
+};
+
+const defaultBase = (kind: RateBase['kind']): RateBase => {
+  switch (kind) {
+    case 'fixed':
+      return { kind, rate: '0' };
+    case 'index':
+      return { kind, index: 'DGS1' };
+    default:
+      return { kind, formulas: [fixedFormula('0'), indexFormula('DGS1')] };
+  }
+};
+
+const fixedFormula = (rate: Decimal): RateFormula => ({
+  base: { kind: 'fixed', rate },
+  rounding_increment: null,
+  spread: '0',
+  floor: null,
+  cap: null,
+});
+
+const indexFormula = (index: RateIndex): RateFormula => ({
+  ...fixedFormula('0'),
+  base: { kind: 'index', index },
+});
+
+interface RateFormulaEditorProps {
+  formula: RateFormula;
+  onChange: (formula: RateFormula) => void;
+}
+
+// Edits a formula and, for greater-of / lesser-of, each formula it compares
+const RateFormulaEditor: React.FC<RateFormulaEditorProps> = ({ formula, onChange }) => {
+  const base = formula.base;
+  const optional = (value: string): Decimal | null => (value === '' ? null : value);
+
+  return (
+    <div className="space-y-2">
+      <div className="grid grid-cols-2 gap-2">
+        <select
+          value={base.kind}
+          onChange={(e) => onChange({ ...formula, base: defaultBase(e.target.value as RateBase['kind']) })}
+          className={inputClass}
+        >
+          <option value="fixed">Fixed Rate</option>
+          <option value="index">Index</option>
+          <option value="greater_of">Greater Of</option>
+          <option value="lesser_of">Lesser Of</option>
+        </select>
+
+        {base.kind === 'fixed' && (
+          <input
+            type="number"
+            value={base.rate}
+            onChange={(e) => onChange({ ...formula, base: { ...base, rate: e.target.value || '0' } })}
+            step="0.01"
+            min="0"
+            placeholder="Rate (%)"
+            className={inputClass}
+          />
+        )}
+
+        {base.kind === 'index' && (
+          <select
+            value={base.index}
+            onChange={(e) => onChange({ ...formula, base: { ...base, index: e.target.value as RateIndex } })}
+            className={inputClass}
+          >
+            {(Object.keys(INDEX_LABELS) as RateIndex[]).map(index => (
+              <option key={index} value={index}>{INDEX_LABELS[index]} ({index})</option>
+            ))}
+          </select>
+        )}
+      </div>
+
+      {(base.kind === 'greater_of' || base.kind === 'lesser_of') && (
+        <div className="pl-3 border-l-2 border-gray-200 space-y-3">
+          {base.formulas.map((child, index) => (
+            <div key={index} className="space-y-1">
+              <RateFormulaEditor
+                formula={child}
+                onChange={(updated) => onChange({
+                  ...formula,
+                  base: { ...base, formulas: base.formulas.map((f, i) => (i === index ? updated : f)) },
+                })}
+              />
+              {base.formulas.length > 1 && (
+                <button
+                  type="button"
+                  onClick={() => onChange({
+                    ...formula,
+                    base: { ...base, formulas: base.formulas.filter((_, i) => i !== index) },
+                  })}
+                  className="text-xs text-red-600 hover:underline"
+                >
+                  Remove
+                </button>
+              )}
+            </div>
+          ))}
+          <button
+            type="button"
+            onClick={() => onChange({
+              ...formula,
+              base: { ...base, formulas: [...base.formulas, fixedFormula('0')] },
+            })}
+            className="px-2 py-1 text-xs border border-gray-300 rounded-md hover:bg-gray-50"
+          >
+            Add Rate
+          </button>
+        </div>
+      )}
+
+      <div className="grid grid-cols-4 gap-2">
+        <input
+          type="number"
+          value={formula.rounding_increment ?? ''}
+          onChange={(e) => onChange({ ...formula, rounding_increment: optional(e.target.value) })}
+          step="0.125"
+          min="0"
+          placeholder="Round to"
+          title="Round the base to the nearest increment (%)"
+          className={inputClass}
+        />
+        <input
+          type="number"
+          value={formula.spread}
+          onChange={(e) => onChange({ ...formula, spread: e.target.value || '0' })}
+          step="0.01"
+          placeholder="Spread"
+          title="Percentage points added to the base"
+          className={inputClass}
+        />
+        <input
+          type="number"
+          value={formula.floor ?? ''}
+          onChange={(e) => onChange({ ...formula, floor: optional(e.target.value) })}
+          step="0.01"
+          min="0"
+          placeholder="Floor"
+          title="Minimum rate (%)"
+          className={inputClass}
+        />
+        <input
+          type="number"
+          value={formula.cap ?? ''}
+          onChange={(e) => onChange({ ...formula, cap: optional(e.target.value) })}
+          step="0.01"
+          min="0"
+          placeholder="Cap"
+          title="Maximum rate (%)"
+          className={inputClass}
+        />
+      </div>
+    </div>
+  );
+};
+
+export default RateFormulaEditor;
@@ -1,5 +1,6 @@
 import React, { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { describeError } from '../errors';
 
 interface SettingsTabProps {
   onApiKeySet: () => void;
@@ -36,7 +37,7 @@ const SettingsTab: React.FC<SettingsTabProps> = ({ onApiKeySet }) => {
         setApiKey('');
       }, 2000);
     } catch (err) {
-      setError('Failed to save API key: ' + err);
+      setError(describeError(err));
     } finally {
       setLoading(false);
     }
@@ -0,0 +1,15 @@
+import { AppError } from './types';
+
+const isAppError = (err: unknown): err is AppError =>
+  typeof err === 'object' && err !== null && 'code' in err && 'message' in err;
+
+// Commands reject with an AppError; anything else is wrapped so callers see one shape
+export const toAppError = (err: unknown): AppError =>
+  isAppError(err)
+    ? err
+    : { code: 'invalid_input', category: 'validation', message: String(err), details: null };
+
+export const describeError = (err: unknown): string => {
+  const error = toAppError(err);
+  return error.details ? `${error.message} (${error.details})` : error.message;
+};
@@ -1,32 +1,356 @@
+// Exact decimal amounts and rates; the backend serializes them as strings
+export type Decimal = string;
+
 export interface StateRate {
   id: number;
   state: string;
-  rate: number;
-  is_variable: boolean;
-  plus_percentage: number;
-  update_frequency: string;
+  formula: RateFormula;
+  update_frequency: UpdateFrequency;
   last_update: string;
   notes: string;
+  compounding: CompoundingMode;
+  day_count: DayCount;
+  rounding: RoundingPolicy;
+  rate_date_rule: RateDateRule;
+  // Set while the row is in the trash
+  deleted_at: string | null;
+  citation: string;
+  source_url: string;
+  effective_date: string | null;
+  verification_note: string;
+  // Who last checked the rate against its source, and when
+  verified_by: string;
+  verified_on: string | null;
+  // Derived from verified_on and update_frequency
+  next_review: string | null;
+}
+
+// How often a rate must be re-verified
+export type UpdateFrequency = 'weekly' | 'monthly' | 'quarterly' | 'semi_annual' | 'annual';
+
+export type AuditAction = 'insert' | 'update' | 'delete' | 'restore' | 'purge';
+
+export type AuditSnapshot = 'before' | 'after';
+
+// One change to a jurisdiction's row, with the row before and after it
+export interface StateRateAudit {
+  id: number;
+  state_rate_id: number;
+  state: string;
+  action: AuditAction;
+  before: StateRate | null;
+  after: StateRate | null;
+  changed_at: string;
+  changed_by: string;
+  reason: string | null;
+  // The row's rate periods either side of a change to them; null otherwise
+  before_periods: StateRatePeriod[] | null;
+  after_periods: StateRatePeriod[] | null;
+}
+
+// Which date fixes a variable rate's index value
+export type RateDateRule =
+  | 'preceding_week'
+  | 'january_first'
+  | 'half_year_first_business_day'
+  | 'verdict_date'
+  | 'entry_date';
+
+// Statutory rate in percent: the base, rounded to the nearest increment, plus
+// the spread, held between the floor and cap
+export interface RateFormula {
+  base: RateBase;
+  rounding_increment: Decimal | null;
+  spread: Decimal;
+  floor: Decimal | null;
+  cap: Decimal | null;
+}
+
+export type RateBase =
+  | { kind: 'fixed'; rate: Decimal }
+  | { kind: 'index'; index: RateIndex }
+  | { kind: 'greater_of'; formulas: RateFormula[] }
+  | { kind: 'lesser_of'; formulas: RateFormula[] };
+
+// FRED series a variable rate floats on
+export type RateIndex = 'DGS1' | 'DPRIME' | 'DPCREDIT' | 'DFF';
+
+export type CompoundingMode =
+  | 'none'
+  | 'annual'
+  | 'semi_annual'
+  | 'monthly'
+  | 'daily'
+  | 'anniversary';
+
+export type DayCount = 'actual_365' | 'actual_actual' | 'actual_360' | 'thirty_360';
+
+export type RoundingStage = 'per_day' | 'per_period' | 'final';
+
+export type RoundingMode = 'half_up' | 'half_even';
+
+export interface RoundingPolicy {
+  stage: RoundingStage;
+  mode: RoundingMode;
+}
+
+export interface StateRatePeriod {
+  id: number;
+  state_id: number;
+  effective_from: string;
+  effective_to: string | null;
+  rate: Decimal;
+  notes: string;
 }
 
 export interface CalcRequest {
   judgment_date: string;
+  verdict_date?: string | null;
   is_federal: boolean;
   state: string;
-  amount: number;
+  amount: Decimal;
   from_date: string;
   to_date: string;
+  day_count?: DayCount | null;
+  payments: Payment[];
+  payment_allocation: PaymentAllocation;
+  schedule_interval: ScheduleInterval;
+}
+
+// A judgment being tracked; jurisdiction is a state name or 'Federal'
+export interface Matter {
+  id: number;
+  caption: string;
+  docket_number: string;
+  court: string;
+  client: string;
+  judgment_date: string;
+  principal: Decimal;
+  jurisdiction: string;
+  created_at: string;
+  updated_at: string;
+  calculation_count: number;
 }
 
+// A calculation saved against a matter, as it was when saved
+export interface SavedCalculation {
+  id: number;
+  matter_id: number;
+  name: string;
+  request: CalcRequest;
+  response: CalcResponse;
+  calculated_at: string;
+}
+
+export interface CalculationSummary {
+  id: number;
+  matter_id: number;
+  name: string;
+  calculated_at: string;
+  from_date: string;
+  to_date: string;
+  rate: Decimal;
+  total_amount: Decimal;
+}
+
+export interface Payment {
+  date: string;
+  amount: Decimal;
+}
+
+export type PaymentAllocation = 'us_rule' | 'pro_rata';
+
+export type ScheduleInterval = 'daily' | 'monthly';
+
 export interface CalcResponse {
-  rate: number;
+  rate: Decimal;
   days: number;
-  interest_amount: number;
-  total_amount: number;
-  rate_source: string;
+  interest_amount: Decimal;
+  total_amount: Decimal;
+  rate_source: RateSource;
   disclaimer: string;
+  periods: InterestPeriod[];
+  day_count: DayCount;
+  ledger: LedgerEntry[];
+  rate_segments: RateSegment[];
+  rate_steps: RateStep[];
+  rate_window: string | null;
+  rounding: RoundingPolicy;
+  per_diem: Decimal;
+  schedule: ScheduleRow[];
+  warnings: CalcWarning[];
+}
+
+export type WarningSeverity = 'info' | 'warning' | 'critical';
+
+export type WarningCode =
+  | 'default_rate_used'
+  | 'partial_index_data'
+  | 'stale_index_data'
+  | 'index_fetch_failed'
+  | 'from_date_before_judgment'
+  | 'future_end_date'
+  | 'stale_rate_row'
+  | 'missing_rate_index'
+  | 'unverified_rate_row'
+  | 'jurisdiction_defaults'
+  | 'missing_citation'
+  | 'inconsistent_rate_row';
+
+// The statutory basis of the rate, for citing in a filing
+export interface RateSource {
+  description: string;
+  citation: string;
+  source_url: string;
+  effective_date: string | null;
+  verification_note: string;
+}
+
+export interface CalcWarning {
+  code: WarningCode;
+  severity: WarningSeverity;
+  message: string;
+}
+
+export interface RateStep {
+  description: string;
+  rate: Decimal;
+}
+
+export interface ScheduleRow {
+  date: string;
+  days: number;
+  interest: Decimal;
+  payments: Decimal;
+  cumulative_interest: Decimal;
+  balance: Decimal;
+}
+
+export interface RateSegment {
+  start_date: string;
+  end_date: string;
+  days: number;
+  rate: Decimal;
+  interest: Decimal;
+}
+
+export interface InterestPeriod {
+  start_date: string;
+  end_date: string;
+  days: number;
+  opening_balance: Decimal;
+  interest: Decimal;
+  closing_balance: Decimal;
+}
+
+export interface LedgerEntry {
+  date: string;
+  days: number;
+  interest_accrued: Decimal;
+  payment: Decimal;
+  applied_to_interest: Decimal;
+  applied_to_principal: Decimal;
+  unpaid_interest: Decimal;
+  principal_balance: Decimal;
+  balance: Decimal;
 }
 
 export interface ApiSettings {
   fred_api_key: string;
 }
+
+export type ErrorCode =
+  | 'invalid_input'
+  | 'invalid_date'
+  | 'invalid_rate_formula'
+  | 'duplicate_state'
+  | 'invalid_import'
+  | 'state_not_found'
+  | 'not_found'
+  | 'api_key_missing'
+  | 'api_request_failed'
+  | 'api_response_invalid'
+  | 'no_observations'
+  | 'database_locked'
+  | 'database_error'
+  | 'schema_too_new'
+  | 'config_error';
+
+export type ErrorCategory = 'validation' | 'not_found' | 'network' | 'database' | 'configuration';
+
+// The error every command rejects with
+export interface AppError {
+  code: ErrorCode;
+  category: ErrorCategory;
+  message: string;
+  details: string | null;
+}
+
+export type TableFormat = 'json' | 'csv';
+
+// What an import would do to the current table
+export interface ImportPreview {
+  added: string[];
+  changed: RowChange[];
+  removed: string[];
+  unchanged: number;
+}
+
+export interface RowChange {
+  state: string;
+  fields: string[];
+}
+
+// A jurisdiction as the app ships it
+export interface SeedRow {
+  state: string;
+  formula: RateFormula;
+  update_frequency: UpdateFrequency;
+  notes: string;
+  compounding: CompoundingMode;
+  day_count: DayCount;
+  rounding: RoundingPolicy;
+  rate_date_rule: RateDateRule;
+  citation: string;
+  source_url: string;
+  effective_date: string | null;
+}
+
+// A shipped update held back because the row was edited locally
+export interface SeedConflict {
+  local: StateRate;
+  shipped: SeedRow;
+  seed_version: number;
+  fields: string[];
+}
+
+export type LintCode =
+  | 'notes_index_mismatch'
+  | 'notes_spread_mismatch'
+  | 'notes_rate_mismatch'
+  | 'notes_compounding_mismatch'
+  | 'adjusted_fixed_rate'
+  | 'single_rate_choice';
+
+// A contradiction between a row's formula, notes and compounding
+export interface LintIssue {
+  state_rate_id: number;
+  state: string;
+  code: LintCode;
+  message: string;
+}
+
+export interface LintReport {
+  checked: number;
+  issues: LintIssue[];
+}
+
+// Sent with a LintReport at startup and after every write to the rate table
+export const LINT_EVENT = 'rate-table-linted';
+
+export interface ResetReport {
+  reset: string[];
+  trashed: string[];
+  // Export of the table taken before the reset
+  backup: string;
+}